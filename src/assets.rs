@@ -1,23 +1,30 @@
-use crate::prelude::*;
+use crate::{cli::CliArgs, prelude::*};
 use bevy_asset_loader::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_loading_state(
-        LoadingState::new(Screen::Loading)
-            .continue_to_state(if cfg!(feature = "dev") {
-                Screen::Game
-            } else {
-                Screen::MainMenu
-            })
-            .load_collection::<SpriteAssets>()
-            .load_collection::<FontAssets>()
-            .load_collection::<SfxAssets>()
-            .load_collection::<MusicAssets>()
-            .load_collection::<WordlistAssets>(),
-    );
+    let has_cli_level = app.world().resource::<CliArgs>().level.is_some();
+    app.add_loading_state(loading_state().continue_to_state(
+        // e.g. `--level 0` to skip the menus while developing
+        if has_cli_level {
+            Screen::Game
+        } else {
+            Screen::MainMenu
+        },
+    ));
     // app.add_systems(Startup, setup_particles);
 }
 
+/// Loads all of the asset collections.
+pub(crate) fn loading_state() -> LoadingState<Screen> {
+    LoadingState::new(Screen::Loading)
+        .load_collection::<SpriteAssets>()
+        .load_collection::<FontAssets>()
+        .load_collection::<SfxAssets>()
+        .load_collection::<MusicAssets>()
+        .load_collection::<WordlistAssets>()
+        .load_collection::<LevelAssets>()
+}
+
 #[allow(dead_code)]
 pub fn assets_exist(
     sprites: Option<Res<SpriteAssets>>,
@@ -31,10 +38,66 @@ pub fn assets_exist(
     /*&& particles.is_some()*/
 }
 
+#[derive(AssetCollection, Resource)]
+pub struct LevelAssets {
+    #[asset(path = "levels.ldtk")]
+    pub project: Handle<LdtkProject>,
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct WordlistAssets {
-    #[asset(path = "en.words.txt")]
-    pub en: Handle<WordListSource>,
+    /// Every `*.words.txt` and `*.phrases.txt` in the `words` folder, keyed by the asset path.
+    // folders can't be loaded on the web so the lists have to be listed there
+    #[cfg_attr(
+        not(target_family = "wasm"),
+        asset(path = "words", collection(typed, mapped))
+    )]
+    #[cfg_attr(
+        target_family = "wasm",
+        asset(
+            paths(
+                "words/en.words.txt",
+                "words/cs.words.txt",
+                "words/de.words.txt",
+                "words/en.phrases.txt",
+                "words/cs.phrases.txt",
+                "words/de.phrases.txt"
+            ),
+            collection(typed, mapped)
+        )
+    )]
+    pub lists: HashMap<String, Handle<WordListSource>>,
+}
+
+impl WordlistAssets {
+    /// Language code taken from the file name, e.g. `cs` for `words/cs.words.txt`.
+    pub fn languages(&self) -> impl Iterator<Item = (&str, &Handle<WordListSource>)> {
+        self.lists.iter().filter_map(|(path, handle)| {
+            let file_name = path.rsplit('/').next()?;
+            Some((file_name.strip_suffix(".words.txt")?, handle))
+        })
+    }
+
+    pub fn get(&self, language: &str) -> Option<&Handle<WordListSource>> {
+        self.languages()
+            .find(|(lang, _)| *lang == language)
+            .map(|(_, handle)| handle)
+    }
+
+    /// Phrase list of the language, from `words/<code>.phrases.txt`.
+    pub fn phrases(&self, language: &str) -> Option<&Handle<WordListSource>> {
+        self.lists.iter().find_map(|(path, handle)| {
+            let file_name = path.rsplit('/').next()?;
+            (file_name.strip_suffix(".phrases.txt")? == language).then_some(handle)
+        })
+    }
+
+    /// Sorted language codes of all of the lists.
+    pub fn language_codes(&self) -> Vec<String> {
+        let mut codes: Vec<_> = self.languages().map(|(lang, _)| lang.to_string()).collect();
+        codes.sort();
+        codes
+    }
 }
 
 // https://github.com/NiklasEi/bevy_asset_loader?tab=readme-ov-file#supported-asset-fields
@@ -134,8 +197,23 @@ pub struct SfxAssets {
 pub struct MusicAssets {
     #[asset(path = "audio/music/main_menu.ogg")]
     pub main_menu: Handle<AudioSource>,
-    #[asset(path = "audio/music/game.ogg")]
-    pub game: Handle<AudioSource>,
+    /// Played once before the main menu loop, if there is one.
+    #[asset(path = "audio/music/main_menu_intro.ogg", optional)]
+    pub main_menu_intro: Option<Handle<AudioSource>>,
+    /// Synced stems of the game track, each one fading in at a higher intensity.
+    /// The drums and the hats are 144 BPM and exactly as long as the full mix.
+    #[asset(
+        paths(
+            "audio/music/game.ogg",
+            "audio/music/game_drums.flac",
+            "audio/music/game_hats.flac"
+        ),
+        collection(typed)
+    )]
+    pub game_layers: Vec<Handle<AudioSource>>,
+    /// Played once before the game loop, if there is one.
+    #[asset(path = "audio/music/game_intro.ogg", optional)]
+    pub game_intro: Option<Handle<AudioSource>>,
 }
 
 // todo: use asset_loader for particles too
@@ -0,0 +1,173 @@
+//! Volume buses the sounds are played through.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Music volume while ducked.
+const DUCK_GAIN: f32 = 0.35;
+/// How long it takes the music to get back to the full volume after ducking.
+const DUCK_RELEASE: Duration = Duration::from_millis(900);
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AudioBus>()
+        .register_type::<BaseVolume>()
+        .register_type::<AudioBuses>()
+        .init_resource::<AudioBuses>()
+        .register_type::<MusicDucking>()
+        .init_resource::<MusicDucking>()
+        .add_systems(
+            Update,
+            (
+                tick_music_ducking,
+                update_sink_volumes.run_if(
+                    resource_changed::<AudioBuses>.or_else(resource_changed::<MusicDucking>),
+                ),
+            )
+                .chain(),
+        );
+}
+
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub(crate) enum AudioBus {
+    /// Scales all of the other buses.
+    Master,
+    Music,
+    Sfx,
+    Ui,
+}
+
+impl AudioBus {
+    pub const ALL: [AudioBus; 4] = [
+        AudioBus::Master,
+        AudioBus::Music,
+        AudioBus::Sfx,
+        AudioBus::Ui,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioBus::Master => "All",
+            AudioBus::Music => "Music",
+            AudioBus::Sfx => "SFX",
+            AudioBus::Ui => "UI",
+        }
+    }
+}
+
+/// Volume of the sound itself, before the bus volume is applied.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub(crate) struct BaseVolume(pub f32);
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub(crate) struct AudioBuses {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub ui: f32,
+    /// Silences every bus for the session, e.g. with `--mute`.
+    #[serde(skip)]
+    pub muted: bool,
+}
+
+impl Default for AudioBuses {
+    fn default() -> Self {
+        Self {
+            master: 0.3,
+            music: 1.,
+            sfx: 1.,
+            ui: 1.,
+            muted: false,
+        }
+    }
+}
+
+impl AudioBuses {
+    pub fn volume(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Master => self.master,
+            AudioBus::Music => self.music,
+            AudioBus::Sfx => self.sfx,
+            AudioBus::Ui => self.ui,
+        }
+    }
+
+    pub fn set_volume(&mut self, bus: AudioBus, volume: f32) {
+        let volume = volume.clamp(0., 1.);
+        match bus {
+            AudioBus::Master => self.master = volume,
+            AudioBus::Music => self.music = volume,
+            AudioBus::Sfx => self.sfx = volume,
+            AudioBus::Ui => self.ui = volume,
+        }
+    }
+
+    /// Volume of the bus including the master volume.
+    pub fn gain(&self, bus: AudioBus) -> f32 {
+        if self.muted {
+            return 0.;
+        }
+        match bus {
+            AudioBus::Master => self.master,
+            _ => self.master * self.volume(bus),
+        }
+    }
+}
+
+/// Briefly lowers the music volume to make room for big events.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct MusicDucking {
+    release: Option<Timer>,
+}
+
+impl MusicDucking {
+    pub fn duck(&mut self) {
+        self.release = Some(Timer::new(DUCK_RELEASE, TimerMode::Once));
+    }
+
+    pub fn gain(&self) -> f32 {
+        match &self.release {
+            Some(timer) => DUCK_GAIN.lerp(1., timer.fraction()),
+            None => 1.,
+        }
+    }
+}
+
+/// Final volume of a sound playing through the bus.
+pub(crate) fn bus_volume(
+    base: f32,
+    bus: AudioBus,
+    buses: &AudioBuses,
+    ducking: &MusicDucking,
+) -> f32 {
+    let duck = if bus == AudioBus::Music {
+        ducking.gain()
+    } else {
+        1.
+    };
+    base * buses.gain(bus) * duck
+}
+
+fn tick_music_ducking(mut ducking: ResMut<MusicDucking>, time: Res<Time>) {
+    // don't trigger the volume update when there's nothing to release
+    or_return_quiet!(ducking.release.is_some());
+    let timer = ducking.release.as_mut().expect("Checked above");
+    if timer.tick(time.delta()).finished() {
+        ducking.release = None;
+    }
+}
+
+fn update_sink_volumes(
+    sink_q: Query<(&AudioSink, &AudioBus, &BaseVolume)>,
+    buses: Res<AudioBuses>,
+    ducking: Res<MusicDucking>,
+) {
+    for (sink, bus, base) in &sink_q {
+        sink.set_volume(bus_volume(base.0, *bus, &buses, &ducking));
+    }
+}
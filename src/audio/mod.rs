@@ -1,10 +1,11 @@
 //! Functionality relating to playing audio in the game.
 
+pub mod bus;
 pub mod music;
 pub mod sfx;
 
 use bevy::prelude::*;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((sfx::plugin, music::plugin));
+    app.add_plugins((bus::plugin, sfx::plugin, music::plugin));
 }
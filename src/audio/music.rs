@@ -1,87 +1,361 @@
+use super::bus::{bus_volume, AudioBus, AudioBuses, BaseVolume, MusicDucking};
 use crate::prelude::*;
 use bevy::{
-    audio::PlaybackMode,
-    ecs::{system::RunSystemOnce as _, world::Command},
+    audio::{PlaybackMode, Volume},
+    ecs::{
+        system::{EntityCommands, RunSystemOnce as _},
+        world::Command,
+    },
 };
+use std::time::Duration;
+
+/// Fade used by [`MusicCommands::play_music`] and [`MusicCommands::stop_music`].
+const DEFAULT_MUSIC_FADE: Duration = Duration::from_millis(800);
+/// How fast the layers follow the [`MusicIntensity`], in gain per second.
+const LAYER_FADE_SPEED: f32 = 0.6;
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<MusicSource>();
+    app.register_type::<MusicSource>()
+        .register_type::<MusicGain>()
+        .register_type::<MusicIntensity>()
+        .init_resource::<MusicIntensity>()
+        .add_systems(
+            Update,
+            (
+                start_music_loops,
+                fade_music,
+                fade_music_layers,
+                apply_music_gain,
+            )
+                .chain(),
+        );
 }
 
 /// Marker component for the soundtrack entity so we can find it later.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct MusicSource;
+struct MusicSource {
+    track: MusicTrack,
+    section: MusicSection,
+    /// Index of the stem, layers with a higher index need a higher [`MusicIntensity`].
+    layer: usize,
+    layer_count: usize,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum MusicSection {
+    /// Played once before the loop.
+    Intro,
+    Loop,
+}
+
+/// Volume of a music entity, combined into its [`BaseVolume`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+struct MusicGain {
+    /// Crossfade between tracks.
+    fade: f32,
+    /// Intensity based volume of the layer.
+    layer: f32,
+}
+
+/// How intense the gameplay is, from 0 to 1.
+/// The layers of the current track fade in as it rises.
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct MusicIntensity(pub f32);
+
+/// Fades the volume of the music entity, despawning it when faded out completely.
+#[derive(Component, Clone)]
+struct MusicFade {
+    from: f32,
+    to: f32,
+    timer: Timer,
+}
+
+impl MusicFade {
+    fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            timer: Timer::new(duration, TimerMode::Once),
+        }
+    }
+
+    fn is_fading_out(&self) -> bool {
+        self.to <= 0.
+    }
+}
 
 /// A custom command used to play soundtracks.
 #[derive(Debug)]
 enum PlayMusic {
-    Track(MusicTrack),
-    Disable,
+    Track { track: MusicTrack, fade: Duration },
+    Disable { fade: Duration },
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum MusicTrack {
     MainMenu,
     Game,
 }
 
+/// Parts of a track - an optional intro followed by the looping stems.
+struct TrackSections {
+    intro: Option<Handle<AudioSource>>,
+    layers: Vec<Handle<AudioSource>>,
+}
+
+impl MusicAssets {
+    fn sections(&self, track: MusicTrack) -> TrackSections {
+        match track {
+            MusicTrack::MainMenu => TrackSections {
+                intro: self.main_menu_intro.as_ref().map(Handle::clone_weak),
+                layers: vec![self.main_menu.clone_weak()],
+            },
+            MusicTrack::Game => TrackSections {
+                intro: self.game_intro.as_ref().map(Handle::clone_weak),
+                layers: self.game_layers.iter().map(Handle::clone_weak).collect(),
+            },
+        }
+    }
+}
+
 impl Command for PlayMusic {
-    /// This command will despawn the current soundtrack, then spawn a new one
-    /// if necessary.
+    /// This command will fade out the current soundtrack, then fade in a new one
+    /// if it's not playing already.
     fn apply(self, world: &mut World) {
         world.run_system_once_with(self, play_music);
     }
 }
 
-// todo: don't restart the same track if it's already playing
 fn play_music(
     In(config): In<PlayMusic>,
-    mut commands: Commands,
-    music_query: Query<Entity, With<MusicSource>>,
+    mut cmd: Commands,
+    music_q: Query<(Entity, &MusicSource, &MusicGain, Option<&MusicFade>)>,
     music: Res<MusicAssets>,
+    intensity: Res<MusicIntensity>,
 ) {
-    for entity in music_query.iter() {
-        commands.entity(entity).despawn_recursive();
+    let (track, fade) = match config {
+        PlayMusic::Track { track, fade } => (Some(track), fade),
+        PlayMusic::Disable { fade } => (None, fade),
+    };
+
+    let mut already_playing = false;
+    for (e, source, gain, current_fade) in &music_q {
+        if Some(source.track) == track {
+            already_playing = true;
+            // bring the track back if it's on its way out
+            if current_fade.is_some_and(MusicFade::is_fading_out) {
+                cmd.entity(e).insert(MusicFade::new(gain.fade, 1., fade));
+            }
+        } else {
+            cmd.entity(e).insert(MusicFade::new(gain.fade, 0., fade));
+        }
     }
 
-    let track = match config {
-        PlayMusic::Track(key) => key,
-        PlayMusic::Disable => return,
-    };
+    let track = or_return_quiet!(track);
+    if already_playing {
+        return;
+    }
 
-    commands.spawn((
-        AudioSourceBundle {
-            source: match track {
-                MusicTrack::MainMenu => music.main_menu.clone_weak(),
-                MusicTrack::Game => music.game.clone_weak(),
+    let sections = music.sections(track);
+    if let Some(intro) = sections.intro {
+        spawn_music_section(
+            &mut cmd,
+            track,
+            MusicSection::Intro,
+            intro,
+            (0, 1),
+            MusicGain {
+                fade: 0.,
+                layer: 1.,
             },
+        )
+        .insert(MusicFade::new(0., 1., fade));
+    } else {
+        spawn_music_layers(&mut cmd, track, sections.layers, 0., intensity.0, |e_cmd| {
+            e_cmd.insert(MusicFade::new(0., 1., fade));
+        });
+    }
+}
+
+fn spawn_music_section<'a>(
+    cmd: &'a mut Commands,
+    track: MusicTrack,
+    section: MusicSection,
+    source: Handle<AudioSource>,
+    (layer, layer_count): (usize, usize),
+    gain: MusicGain,
+) -> EntityCommands<'a> {
+    cmd.spawn((
+        AudioSourceBundle {
+            source,
             settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
+                mode: match section {
+                    MusicSection::Intro => PlaybackMode::Once,
+                    MusicSection::Loop => PlaybackMode::Loop,
+                },
+                // the actual volume is set once the sink exists
+                volume: Volume::new(0.),
                 ..default()
             },
         },
-        MusicSource,
-    ));
+        MusicSource {
+            track,
+            section,
+            layer,
+            layer_count,
+        },
+        gain,
+        AudioBus::Music,
+        BaseVolume(0.),
+    ))
+}
+
+/// Spawn all of the stems in the same frame so they stay in sync.
+fn spawn_music_layers(
+    cmd: &mut Commands,
+    track: MusicTrack,
+    layers: Vec<Handle<AudioSource>>,
+    fade: f32,
+    intensity: f32,
+    mut setup: impl FnMut(&mut EntityCommands),
+) {
+    let layer_count = layers.len();
+    for (layer, source) in layers.into_iter().enumerate() {
+        let gain = MusicGain {
+            fade,
+            layer: layer_target_gain(layer, layer_count, intensity),
+        };
+        let mut e_cmd = spawn_music_section(
+            cmd,
+            track,
+            MusicSection::Loop,
+            source,
+            (layer, layer_count),
+            gain,
+        );
+        setup(&mut e_cmd);
+    }
+}
+
+/// The first layer is always playing, the others are spread over the intensity range.
+fn layer_target_gain(layer: usize, layer_count: usize, intensity: f32) -> f32 {
+    if layer == 0 || intensity >= layer as f32 / layer_count as f32 {
+        1.
+    } else {
+        0.
+    }
+}
+
+fn start_music_loops(
+    intro_q: Query<(
+        Entity,
+        &MusicSource,
+        &AudioSink,
+        &MusicGain,
+        Option<&MusicFade>,
+    )>,
+    music: Res<MusicAssets>,
+    intensity: Res<MusicIntensity>,
+    mut cmd: Commands,
+) {
+    for (e, source, sink, gain, fade) in &intro_q {
+        or_continue_quiet!(source.section == MusicSection::Intro && sink.empty());
+        cmd.entity(e).despawn_recursive();
+        let layers = music.sections(source.track).layers;
+        spawn_music_layers(
+            &mut cmd,
+            source.track,
+            layers,
+            gain.fade,
+            intensity.0,
+            |e_cmd| {
+                if let Some(fade) = fade {
+                    e_cmd.insert(fade.clone());
+                }
+            },
+        );
+    }
+}
+
+fn fade_music(
+    mut fade_q: Query<(Entity, &mut MusicFade, &mut MusicGain)>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (e, mut fade, mut gain) in &mut fade_q {
+        fade.timer.tick(time.delta());
+        gain.fade = fade.from.lerp(fade.to, fade.timer.fraction());
+
+        if fade.timer.finished() {
+            if fade.is_fading_out() {
+                cmd.entity(e).despawn_recursive();
+            } else {
+                cmd.entity(e).remove::<MusicFade>();
+            }
+        }
+    }
+}
+
+fn fade_music_layers(
+    mut layer_q: Query<(&MusicSource, &mut MusicGain)>,
+    intensity: Res<MusicIntensity>,
+    time: Res<Time>,
+) {
+    let max_step = LAYER_FADE_SPEED * time.delta_seconds();
+    for (source, mut gain) in &mut layer_q {
+        let target = layer_target_gain(source.layer, source.layer_count, intensity.0);
+        // don't trigger the volume update for the layers that are already there
+        if gain.layer != target {
+            gain.layer += (target - gain.layer).clamp(-max_step, max_step);
+        }
+    }
+}
+
+fn apply_music_gain(
+    mut music_q: Query<
+        (&MusicGain, &mut BaseVolume, &AudioBus, Option<&AudioSink>),
+        Or<(Changed<MusicGain>, Added<AudioSink>)>,
+    >,
+    buses: Res<AudioBuses>,
+    ducking: Res<MusicDucking>,
+) {
+    for (gain, mut volume, bus, sink) in &mut music_q {
+        volume.0 = gain.fade * gain.layer;
+        if let Some(sink) = sink {
+            sink.set_volume(bus_volume(volume.0, *bus, &buses, &ducking));
+        }
+    }
 }
 
 /// An extension trait with convenience methods for music commands.
 pub trait MusicCommands {
-    /// Play a track, replacing the current one.
+    /// Crossfade to a track, nothing happens if the track is already playing.
     /// music will loop.
-    fn play_music(&mut self, track: MusicTrack);
+    fn play_music(&mut self, track: MusicTrack) {
+        self.play_music_with_fade(track, DEFAULT_MUSIC_FADE);
+    }
+
+    fn play_music_with_fade(&mut self, track: MusicTrack, fade: Duration);
+
+    /// Fade out the current soundtrack.
+    #[allow(dead_code)]
+    fn stop_music(&mut self) {
+        self.stop_music_with_fade(DEFAULT_MUSIC_FADE);
+    }
 
-    /// Stop the current soundtrack.
-    fn stop_music(&mut self);
+    fn stop_music_with_fade(&mut self, fade: Duration);
 }
 
 impl MusicCommands for Commands<'_, '_> {
-    fn play_music(&mut self, track: MusicTrack) {
-        self.add(PlayMusic::Track(track));
+    fn play_music_with_fade(&mut self, track: MusicTrack, fade: Duration) {
+        self.add(PlayMusic::Track { track, fade });
     }
 
-    fn stop_music(&mut self) {
-        self.add(PlayMusic::Disable);
+    fn stop_music_with_fade(&mut self, fade: Duration) {
+        self.add(PlayMusic::Disable { fade });
     }
 }
@@ -1,5 +1,6 @@
+use super::bus::{bus_volume, AudioBus, AudioBuses, BaseVolume, MusicDucking};
 use crate::prelude::*;
-use bevy::ecs::world::Command;
+use bevy::{audio::Volume, ecs::world::Command};
 
 pub(super) fn plugin(_app: &mut App) {
     // No setup required for this plugin.
@@ -10,22 +11,54 @@ pub(super) fn plugin(_app: &mut App) {
 pub(crate) enum Sfx {
     ButtonClick,
     ButtonHover,
+    /// Damage stage of the tile the word belongs to.
     FinishWord(usize),
+    Mistype,
+    RockLanded,
+    PlateToggled,
+    PlayerHurt,
+    BossStaggered,
+}
+
+impl Sfx {
+    fn bus(&self) -> AudioBus {
+        match self {
+            Sfx::ButtonClick | Sfx::ButtonHover => AudioBus::Ui,
+            _ => AudioBus::Sfx,
+        }
+    }
 }
 
 impl SfxAssets {
-    fn play(&self, sfx: Sfx, world: &mut World, settings: PlaybackSettings) {
-        let rng = &mut thread_rng();
+    fn play(&self, sfx: Sfx, world: &mut World, mut settings: PlaybackSettings) {
+        let bus = sfx.bus();
+        let mut game_rng = world.resource_mut::<GameRng>();
+        let rng = &mut game_rng.sfx;
         let source = match sfx {
             Sfx::ButtonClick => self.button_click.clone_weak(),
             Sfx::ButtonHover => self.button_hover.clone_weak(),
-            Sfx::FinishWord(1) => self.hit_1.choose(rng).unwrap().clone_weak(),
+            Sfx::FinishWord(0 | 1) => self.hit_1.choose(rng).unwrap().clone_weak(),
             Sfx::FinishWord(2) => self.hit_2.choose(rng).unwrap().clone_weak(),
-            Sfx::FinishWord(3) => self.hit_3.choose(rng).unwrap().clone_weak(),
-            Sfx::FinishWord(_) => todo!(),
+            Sfx::FinishWord(_) => self.hit_3.choose(rng).unwrap().clone_weak(),
+            Sfx::Mistype => self.hit_1.choose(rng).unwrap().clone_weak(),
+            Sfx::RockLanded => self.hit_3.choose(rng).unwrap().clone_weak(),
+            Sfx::PlateToggled => self.button_click.clone_weak(),
+            Sfx::PlayerHurt => self.hit_2.choose(rng).unwrap().clone_weak(),
+            Sfx::BossStaggered => self.hit_3.choose(rng).unwrap().clone_weak(),
         };
 
-        world.spawn(AudioBundle { source, settings });
+        let base_volume = settings.volume.get();
+        settings.volume = Volume::new(bus_volume(
+            base_volume,
+            bus,
+            world.resource::<AudioBuses>(),
+            world.resource::<MusicDucking>(),
+        ));
+        world.spawn((
+            AudioBundle { source, settings },
+            bus,
+            BaseVolume(base_volume),
+        ));
     }
 }
 
@@ -0,0 +1,269 @@
+//! Command-line options of the native builds, e.g. to jump straight into a level with
+//! `--level Level_2 --seed 42 --skip-splash`.
+//! They override the settings for the session without being saved.
+
+use crate::{
+    game::{campaign::campaign_levels, goal::LevelIndex},
+    prelude::*,
+    rng::SeedSource,
+};
+
+pub const USAGE: &str = "\
+Options:
+  --level <name|index>   start the level right after loading
+  --seed <random|daily|number>
+  --replay <path>        watch a stored replay
+  --wordlist <path>      use the word list instead of the one of the language
+  --bindings <l,r,j>     letters of the left, right and jump keys, e.g. a,t,n
+  --mute
+  --window <WxH>         window size, e.g. 1280x720
+  --skip-splash
+  --help";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, apply_cli_args)
+        .add_systems(
+            OnEnter(Screen::Splash),
+            skip_splash.run_if(|cli: Res<CliArgs>| cli.skip_splash),
+        )
+        .add_systems(
+            OnExit(Screen::Loading),
+            (apply_cli_level, apply_cli_wordlist),
+        );
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LevelArg {
+    Index(usize),
+    /// LDtk identifier such as `Level_2`, or the shown name `Level 2`.
+    Name(String),
+}
+
+impl LevelArg {
+    fn parse(level: &str) -> Self {
+        match level.parse() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Name(level.to_string()),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct CliArgs {
+    pub(crate) level: Option<LevelArg>,
+    /// Takes precedence over the [`SeedSource`] of the settings.
+    pub(crate) seed: Option<SeedSource>,
+    pub(crate) replay: Option<String>,
+    pub(crate) wordlist: Option<String>,
+    pub(crate) bindings: Option<PlayerBindings>,
+    pub(crate) mute: bool,
+    pub(crate) window: Option<UVec2>,
+    pub(crate) skip_splash: bool,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CliError {
+    #[error("missing value of {0}")]
+    MissingValue(String),
+    #[error("invalid value '{value}' of {arg}")]
+    InvalidValue { arg: String, value: String },
+    #[error("unknown option {0}")]
+    Unknown(String),
+    #[error("{0} needs the file system, it's not supported on the web")]
+    Unsupported(String),
+    #[error("usage")]
+    Help,
+}
+
+impl CliArgs {
+    /// Parse the process arguments, the web builds don't have any.
+    pub fn from_env() -> Result<Self, CliError> {
+        if cfg!(target_family = "wasm") {
+            return Ok(Self::default());
+        }
+        Self::parse(std::env::args().skip(1))
+    }
+
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| CliError::MissingValue(arg.clone()))
+            };
+            match arg.as_str() {
+                "--level" => cli.level = Some(LevelArg::parse(&value()?)),
+                "--seed" => cli.seed = Some(parse_value(&arg, value()?, SeedSource::parse)?),
+                "--replay" | "--wordlist" if cfg!(target_family = "wasm") => {
+                    return Err(CliError::Unsupported(arg))
+                }
+                "--replay" => cli.replay = Some(value()?),
+                "--wordlist" => cli.wordlist = Some(value()?),
+                "--bindings" => {
+                    cli.bindings = Some(parse_value(&arg, value()?, PlayerBindings::parse)?)
+                }
+                "--window" => cli.window = Some(parse_value(&arg, value()?, parse_window_size)?),
+                "--mute" => cli.mute = true,
+                "--skip-splash" => cli.skip_splash = true,
+                "--help" | "-h" => return Err(CliError::Help),
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+        Ok(cli)
+    }
+}
+
+fn parse_value<T>(
+    arg: &str,
+    value: String,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Result<T, CliError> {
+    parse(&value).ok_or_else(|| CliError::InvalidValue {
+        arg: arg.to_string(),
+        value,
+    })
+}
+
+/// Parse e.g. `1280x720`.
+fn parse_window_size(size: &str) -> Option<UVec2> {
+    let (width, height) = size.split_once('x')?;
+    let size = UVec2::new(width.parse().ok()?, height.parse().ok()?);
+    (size.min_element() > 0).then_some(size)
+}
+
+fn apply_cli_args(
+    cli: Res<CliArgs>,
+    mut bindings: ResMut<PlayerBindings>,
+    mut buses: ResMut<AudioBuses>,
+) {
+    if let Some(cli_bindings) = &cli.bindings {
+        *bindings = cli_bindings.clone();
+    }
+    buses.muted = cli.mute;
+}
+
+fn skip_splash(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Loading);
+}
+
+fn apply_cli_level(
+    cli: Res<CliArgs>,
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    mut lvl_index: ResMut<LevelIndex>,
+) {
+    let level = or_return_quiet!(cli.level.as_ref());
+    let levels = campaign_levels(&levels, &projects);
+    let index = match level {
+        LevelArg::Index(index) => Some(*index).filter(|index| *index < levels.len()),
+        LevelArg::Name(name) => {
+            let name = name.replace('_', " ");
+            levels
+                .iter()
+                .position(|level| level.name.eq_ignore_ascii_case(&name))
+        }
+    };
+    let Some(index) = index else {
+        warn!("No level {level:?} among the {} levels", levels.len());
+        return;
+    };
+    lvl_index.0 = index;
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn apply_cli_wordlist(
+    cli: Res<CliArgs>,
+    language: Res<WordListLanguage>,
+    mut wordlist_assets: ResMut<WordlistAssets>,
+    mut wordlists: ResMut<Assets<WordListSource>>,
+) {
+    let path = or_return_quiet!(cli.wordlist.as_ref());
+    match crate::word_loader::read_word_list(path.as_ref()) {
+        // replaces the list of the selected language for the session
+        Ok(source) => {
+            let handle = wordlists.add(source);
+            wordlist_assets
+                .lists
+                .insert(format!("words/{}.words.txt", language.0), handle);
+        }
+        Err(err) => warn!("Failed to load the word list {path}: {err}"),
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn apply_cli_wordlist() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, CliError> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_all_options() {
+        let cli = parse(&[
+            "--level",
+            "Level_2",
+            "--seed",
+            "42",
+            "--wordlist",
+            "words.txt",
+            "--bindings",
+            "a,d,W",
+            "--mute",
+            "--window",
+            "1280x720",
+            "--skip-splash",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli,
+            CliArgs {
+                level: Some(LevelArg::Name("Level_2".to_string())),
+                seed: Some(SeedSource::Fixed(42)),
+                replay: None,
+                wordlist: Some("words.txt".to_string()),
+                bindings: Some(PlayerBindings {
+                    left: KeyCode::KeyA,
+                    right: KeyCode::KeyD,
+                    jump: KeyCode::KeyW,
+                }),
+                mute: true,
+                window: Some(UVec2::new(1280, 720)),
+                skip_splash: true,
+            }
+        );
+        assert_eq!(
+            parse(&["--level", "3"]).unwrap().level,
+            Some(LevelArg::Index(3))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert_eq!(
+            parse(&["--seed"]),
+            Err(CliError::MissingValue("--seed".to_string()))
+        );
+        for (arg, value) in [
+            ("--bindings", "a,a,n"),
+            ("--bindings", "a,t"),
+            ("--window", "0x720"),
+        ] {
+            assert_eq!(
+                parse(&[arg, value]),
+                Err(CliError::InvalidValue {
+                    arg: arg.to_string(),
+                    value: value.to_string(),
+                })
+            );
+        }
+        assert_eq!(
+            parse(&["--fullscreen"]),
+            Err(CliError::Unknown("--fullscreen".to_string()))
+        );
+    }
+}
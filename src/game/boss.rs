@@ -0,0 +1,292 @@
+//! Bosses with a queue of phrases instead of a health bar.
+//! Every phrase typed out staggers the boss, running out of the phrases of a phase
+//! moves it to the next phase with a nastier attack pattern.
+
+use crate::{
+    game::{
+        enemy::Enemy,
+        rock::{RockState, Solid},
+        word::{insert_floating_words, WordList},
+    },
+    prelude::*,
+};
+use bevy::color::palettes::tailwind;
+use bevy_trauma_shake::Shakes;
+use std::time::Duration;
+
+/// Phrase count, attack pattern and time between the attacks of each phase.
+const PHASES: [(usize, &[BossAttack], u64); 3] = [
+    (2, &[BossAttack::Stomp], 3500),
+    (2, &[BossAttack::Charge, BossAttack::Stomp], 3000),
+    (
+        3,
+        &[BossAttack::Quake, BossAttack::Charge, BossAttack::Stomp],
+        2500,
+    ),
+];
+/// How long the boss doesn't attack after a finished phrase.
+const STAGGER_MS: u64 = 1200;
+/// Distance in tiles the stomp hurts the player from.
+const STOMP_RANGE: f32 = 2.;
+const QUAKE_RANGE: f32 = 6.;
+const ATTACK_DAMAGE: u32 = 1;
+/// Cells the boss rushes over at most during a charge.
+const CHARGE_CELLS: i32 = 3;
+const CHARGE_STEP_MS: u64 = 90;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<BossBundle>("Boss")
+        .register_type::<Boss>()
+        .add_systems(
+            Update,
+            (
+                on_boss_spawned.run_if(resource_exists::<WordList>),
+                stagger_bosses,
+                recover_bosses,
+                boss_attacks,
+            )
+                .chain()
+                .run_if(level_ready),
+        );
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BossAttack {
+    /// Hurts the player when they're close.
+    Stomp,
+    /// Rushes a few cells towards the player.
+    Charge,
+    /// Shakes the whole level, hurting the player unless they're in the air.
+    Quake,
+}
+
+#[derive(Reflect, Debug)]
+pub(crate) struct BossPhase {
+    /// Phrases left in the phase, the current one goes first.
+    phrases: Vec<String>,
+    attacks: Vec<BossAttack>,
+    attack_interval_ms: u64,
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct Boss {
+    /// Phases left, the current one goes first.
+    phases: Vec<BossPhase>,
+    attack_i: usize,
+    attack_timer: Timer,
+    stagger: Option<Timer>,
+}
+
+impl Boss {
+    fn current_phrase(&self) -> Option<&String> {
+        self.phases.first().and_then(|phase| phase.phrases.first())
+    }
+
+    fn reset_attack_timer(&mut self) {
+        if let Some(phase) = self.phases.first() {
+            self.attack_timer = Timer::new(
+                Duration::from_millis(phase.attack_interval_ms),
+                TimerMode::Repeating,
+            );
+        }
+    }
+
+    /// Drop the finished phrase, returns false when there's nothing left.
+    fn finish_phrase(&mut self) -> bool {
+        let Some(phase) = self.phases.first_mut() else {
+            return false;
+        };
+        if !phase.phrases.is_empty() {
+            phase.phrases.remove(0);
+        }
+        if phase.phrases.is_empty() {
+            self.phases.remove(0);
+            self.attack_i = 0;
+        }
+        self.current_phrase().is_some()
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+struct BossBundle {
+    boss: Boss,
+    movable: Movable,
+    #[with(collision_layers)]
+    collision_layers: CollisionLayers,
+    #[grid_coords]
+    grid_coords: GridCoords,
+    #[sprite_sheet_bundle]
+    sprite_sheet_bundle: LdtkSpriteSheetBundle,
+}
+
+fn collision_layers(_: &EntityInstance) -> CollisionLayers {
+    GamePhysicsLayer::membership(GamePhysicsLayer::Enemy)
+}
+
+fn on_boss_spawned(
+    mut boss_q: Query<(Entity, &mut Boss, &mut Sprite), Added<Boss>>,
+    wordlist: Res<WordList>,
+    fonts: Res<FontAssets>,
+    mut rng: ResMut<GameRng>,
+    mut cmd: Commands,
+) {
+    for (e, mut boss, mut sprite) in &mut boss_q {
+        sprite.color = tailwind::PURPLE_400.into();
+        let total = PHASES.iter().map(|(count, ..)| count).sum();
+        let mut phrases = wordlist.choose_phrases(total, &mut rng.words).into_iter();
+        boss.phases = PHASES
+            .iter()
+            .map(|(count, attacks, attack_interval_ms)| BossPhase {
+                phrases: phrases.by_ref().take(*count).collect(),
+                attacks: attacks.to_vec(),
+                attack_interval_ms: *attack_interval_ms,
+            })
+            .filter(|phase| !phase.phrases.is_empty())
+            .collect();
+        boss.reset_attack_timer();
+
+        let phrase = or_continue!(boss.current_phrase()).clone();
+        let mut e_cmd = or_continue!(cmd.get_entity(e));
+        e_cmd.try_insert(Collider::rectangle(
+            TILE_SIZE as f32 * 0.8,
+            TILE_SIZE as f32 * 0.8,
+        ));
+        insert_floating_words(&mut e_cmd, vec![phrase], fonts.tile.clone_weak());
+    }
+}
+
+/// Stagger the boss when its phrase gets typed out, defeating it after the last one.
+fn stagger_bosses(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    mut boss_q: Query<(&mut Boss, &WordTile)>,
+    mut shake: Shakes,
+    mut ducking: ResMut<MusicDucking>,
+    mut cmd: Commands,
+) {
+    for ev in word_tile_evr
+        .read()
+        .filter(|ev| matches!(ev.kind, WordTileEventKind::TileFinished { .. }))
+    {
+        let (mut boss, word) = or_continue_quiet!(boss_q.get_mut(ev.e));
+        cmd.play_sfx(Sfx::BossStaggered);
+        if !boss.finish_phrase() {
+            ducking.duck();
+            shake.add_trauma(0.6);
+            cmd.tween_text_alpha(word.text_e(), 0.0, 300, EaseFunction::QuadraticIn);
+            let mut e_cmd = or_continue!(cmd.get_entity(ev.e));
+            e_cmd
+                .remove::<(Boss, Collider)>()
+                .try_insert(DespawnOnTweenCompleted::Itself);
+            cmd.tween_sprite_color(ev.e, Color::NONE, 600, EaseFunction::QuadraticIn);
+            continue;
+        }
+
+        shake.add_trauma(0.35);
+        boss.stagger = Some(Timer::new(
+            Duration::from_millis(STAGGER_MS),
+            TimerMode::Once,
+        ));
+        cmd.tween_sprite_color_absolute(
+            ev.e,
+            Color::WHITE,
+            tailwind::PURPLE_400.into(),
+            STAGGER_MS,
+            EaseFunction::QuadraticOut,
+        );
+    }
+}
+
+/// Show the next phrase once the stagger is over.
+fn recover_bosses(
+    mut boss_q: Query<(Entity, &mut Boss, &mut WordTile, &GridCoords)>,
+    mut lookup: ResMut<LevelEntityLookup>,
+    mut word_tile_evw: EventWriter<WordTileEvent>,
+    time: Res<Time>,
+) {
+    for (e, mut boss, mut word, coords) in &mut boss_q {
+        let stagger = or_continue_quiet!(boss.stagger.as_mut());
+        or_continue_quiet!(stagger.tick(time.delta()).finished());
+        boss.stagger = None;
+        boss.reset_attack_timer();
+        let phrase = or_continue!(boss.current_phrase()).clone();
+        // the finished phrase took the boss out of the lookup, back on top of whatever moved in
+        lookup.occupy(e, *coords);
+        word_tile_evw.send(WordTileEvent {
+            e,
+            kind: word.replace_words(vec![phrase]),
+        });
+    }
+}
+
+fn boss_attacks(
+    mut boss_q: Query<(
+        Entity,
+        &mut Boss,
+        &mut GridCoords,
+        &mut Movable,
+        &mut Sprite,
+    )>,
+    player_q: Query<(&GridCoords, &Grounded), (With<Player>, Without<Boss>)>,
+    mut rock_q: Query<&mut RockState>,
+    solid_q: Query<(), Solid>,
+    enemy_q: Query<(), Or<(With<Enemy>, With<Boss>)>>,
+    mut lookup: ResMut<LevelEntityLookup>,
+    mut damage_evw: EventWriter<DamagePlayer>,
+    mut shake: Shakes,
+    time: Res<Time>,
+) {
+    let (player, grounded) = or_return_quiet!(player_q.get_single());
+    for (e, mut boss, mut coords, mut movable, mut sprite) in &mut boss_q {
+        or_continue_quiet!(boss.stagger.is_none());
+        or_continue_quiet!(boss.attack_timer.tick(time.delta()).just_finished());
+        let phase = or_continue_quiet!(boss.phases.first());
+        let attack = phase.attacks[boss.attack_i % phase.attacks.len()];
+        boss.attack_i += 1;
+
+        match attack {
+            BossAttack::Stomp => {
+                shake.add_trauma(0.25);
+                if coords.distance(player) <= STOMP_RANGE {
+                    damage_evw.send(DamagePlayer(ATTACK_DAMAGE));
+                }
+            }
+            BossAttack::Charge => {
+                let dir = (player.x - coords.x).signum();
+                if dir == 0 {
+                    continue;
+                }
+                sprite.flip_x = dir < 0;
+                let solid = |c: &GridCoords| lookup.get(c).is_some_and(|e| solid_q.contains(*e));
+                let enemy = |c: &GridCoords| lookup.get(c).is_some_and(|e| enemy_q.contains(*e));
+                let mut target = *coords;
+                for _ in 0..CHARGE_CELLS {
+                    let next = GridCoords::new(target.x + dir, target.y);
+                    // stop at walls, ledges and other enemies
+                    if solid(&next) || !solid(&next.down()) || enemy(&next) {
+                        break;
+                    }
+                    target = next;
+                }
+                or_continue_quiet!(target != *coords);
+                movable.tween_duration_ms =
+                    CHARGE_STEP_MS * (target.x - coords.x).unsigned_abs() as u64;
+                movable.easing = Some(EaseFunction::QuadraticIn);
+                lookup.upsert(e, &coords, target);
+                *coords = target;
+            }
+            BossAttack::Quake => {
+                shake.add_trauma(0.5);
+                if grounded.is_grounded() && coords.distance(player) <= QUAKE_RANGE {
+                    damage_evw.send(DamagePlayer(ATTACK_DAMAGE));
+                }
+                // the rocks without support fall right away
+                for mut state in &mut rock_q {
+                    if matches!(*state, RockState::Resting) {
+                        *state = RockState::wobbling();
+                    }
+                }
+            }
+        }
+    }
+}
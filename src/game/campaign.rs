@@ -0,0 +1,136 @@
+//! Order of the levels and the progress through them.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CampaignProgress>()
+        .init_resource::<CampaignProgress>();
+}
+
+/// Level of the LDtk project.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CampaignLevel {
+    pub iid: String,
+    pub name: String,
+}
+
+impl CampaignLevel {
+    fn new(iid: impl Into<String>, identifier: &str) -> Self {
+        Self {
+            iid: iid.into(),
+            name: identifier.replace('_', " "),
+        }
+    }
+}
+
+/// Levels in the order of the LDtk project, empty until the project is loaded.
+pub(crate) fn campaign_levels(
+    levels: &LevelAssets,
+    projects: &Assets<LdtkProject>,
+) -> Vec<CampaignLevel> {
+    projects
+        .get(&levels.project)
+        .map(|project| {
+            project
+                .iter_raw_levels()
+                .map(|level| CampaignLevel::new(&level.iid, &level.identifier))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LevelState {
+    Locked,
+    Unlocked,
+    Completed,
+}
+
+/// Levels completed by the player, keyed by the level iid so they survive the reordering of the levels.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[reflect(Resource)]
+pub(crate) struct CampaignProgress {
+    completed: Vec<String>,
+}
+
+impl CampaignProgress {
+    pub fn complete(&mut self, iid: &str) {
+        if !self.is_completed(iid) {
+            self.completed.push(iid.to_string());
+        }
+    }
+
+    pub fn is_completed(&self, iid: &str) -> bool {
+        self.completed.iter().any(|completed| completed == iid)
+    }
+
+    pub fn has_started(&self) -> bool {
+        !self.completed.is_empty()
+    }
+
+    /// The first level is always unlocked, the others once the previous one is completed.
+    pub fn level_state(&self, levels: &[CampaignLevel], index: usize) -> LevelState {
+        let Some(level) = levels.get(index) else {
+            return LevelState::Locked;
+        };
+        if self.is_completed(&level.iid) {
+            LevelState::Completed
+        } else if index == 0 || self.is_completed(&levels[index - 1].iid) {
+            LevelState::Unlocked
+        } else {
+            LevelState::Locked
+        }
+    }
+
+    /// The first level that hasn't been completed yet.
+    pub fn next_level(&self, levels: &[CampaignLevel]) -> Option<usize> {
+        levels
+            .iter()
+            .position(|level| !self.is_completed(&level.iid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels() -> Vec<CampaignLevel> {
+        ["a", "b", "c"]
+            .into_iter()
+            .map(|iid| CampaignLevel::new(iid, "Level_0"))
+            .collect()
+    }
+
+    #[test]
+    fn completing_a_level_unlocks_the_next_one() {
+        let levels = levels();
+        let mut progress = CampaignProgress::default();
+        assert_eq!(progress.level_state(&levels, 0), LevelState::Unlocked);
+        assert_eq!(progress.level_state(&levels, 1), LevelState::Locked);
+        assert_eq!(progress.next_level(&levels), Some(0));
+
+        progress.complete("a");
+        progress.complete("a");
+        assert_eq!(progress.level_state(&levels, 0), LevelState::Completed);
+        assert_eq!(progress.level_state(&levels, 1), LevelState::Unlocked);
+        assert_eq!(progress.level_state(&levels, 2), LevelState::Locked);
+        assert_eq!(progress.next_level(&levels), Some(1));
+    }
+
+    #[test]
+    fn finished_campaign_has_no_next_level() {
+        let levels = levels();
+        let mut progress = CampaignProgress::default();
+        for level in &levels {
+            progress.complete(&level.iid);
+        }
+        assert_eq!(progress.next_level(&levels), None);
+        assert_eq!(progress.level_state(&levels, 3), LevelState::Locked);
+    }
+
+    #[test]
+    fn level_names_come_from_identifiers() {
+        assert_eq!(CampaignLevel::new("a", "Level_2").name, "Level 2");
+    }
+}
@@ -0,0 +1,214 @@
+//! Enemies patrolling the levels, defeated by typing the word floating above them.
+
+use crate::{
+    game::{rock::Solid, word::insert_floating_words, word::WordList},
+    prelude::*,
+};
+use avian2d::prelude::{SpatialQuery, SpatialQueryFilter};
+use bevy::color::palettes::tailwind;
+use std::time::Duration;
+
+const PATROL_STEP_MS: u64 = 700;
+const CHASE_STEP_MS: u64 = 450;
+/// Distance in tiles the enemy notices the player from.
+const CHASE_RANGE: f32 = 4.;
+const ENEMY_WORD_COUNT: usize = 1;
+const CONTACT_DAMAGE: u32 = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<EnemyBundle>("Enemy")
+        .register_type::<EnemyAi>()
+        .add_systems(
+            Update,
+            (
+                on_enemy_spawned.run_if(resource_exists::<WordList>),
+                move_enemies,
+                release_distant_floating_words,
+                damage_player_on_contact,
+                defeat_enemies,
+            )
+                .chain()
+                .run_if(level_ready),
+        );
+}
+
+#[derive(Component, Debug, Default)]
+pub(crate) struct Enemy;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(crate) struct EnemyAi {
+    /// Walking direction, -1 or 1.
+    dir: i32,
+    step: Timer,
+}
+
+impl Default for EnemyAi {
+    fn default() -> Self {
+        Self {
+            dir: 1,
+            step: Timer::new(Duration::from_millis(PATROL_STEP_MS), TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+struct EnemyBundle {
+    enemy: Enemy,
+    ai: EnemyAi,
+    movable: Movable,
+    #[with(collision_layers)]
+    collision_layers: CollisionLayers,
+    #[grid_coords]
+    grid_coords: GridCoords,
+    #[sprite_sheet_bundle]
+    sprite_sheet_bundle: LdtkSpriteSheetBundle,
+}
+
+fn collision_layers(_: &EntityInstance) -> CollisionLayers {
+    GamePhysicsLayer::membership(GamePhysicsLayer::Enemy)
+}
+
+fn on_enemy_spawned(
+    mut enemy_q: Query<(Entity, &mut Sprite), Added<Enemy>>,
+    wordlist: Res<WordList>,
+    fonts: Res<FontAssets>,
+    mut rng: ResMut<GameRng>,
+    mut cmd: Commands,
+) {
+    for (e, mut sprite) in &mut enemy_q {
+        sprite.color = tailwind::RED_400.into();
+        let words = wordlist.choose_enemy(ENEMY_WORD_COUNT, &mut rng.words);
+        let mut e_cmd = or_continue!(cmd.get_entity(e));
+        e_cmd.try_insert(Collider::rectangle(
+            TILE_SIZE as f32 * 0.6,
+            TILE_SIZE as f32 * 0.6,
+        ));
+        insert_floating_words(&mut e_cmd, words, fonts.tile.clone_weak());
+    }
+}
+
+/// Walk back and forth on the grid, chasing the player when they're close on the same row.
+fn move_enemies(
+    mut enemy_q: Query<
+        (
+            Entity,
+            &mut GridCoords,
+            &mut EnemyAi,
+            &mut Movable,
+            &mut Sprite,
+        ),
+        (With<Enemy>, Without<Player>),
+    >,
+    player_q: Query<&GridCoords, With<Player>>,
+    solid_q: Query<(), Solid>,
+    other_enemy_q: Query<(), With<Enemy>>,
+    mut lookup: ResMut<LevelEntityLookup>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    let player = player_q.get_single().ok();
+    for (e, mut coords, mut ai, mut movable, mut sprite) in &mut enemy_q {
+        if !ai.step.tick(time.delta()).finished() {
+            continue;
+        }
+        let chasing = player
+            .is_some_and(|player| player.y == coords.y && coords.distance(player) <= CHASE_RANGE);
+        let step_ms = if chasing {
+            CHASE_STEP_MS
+        } else {
+            PATROL_STEP_MS
+        };
+        ai.step = Timer::new(Duration::from_millis(step_ms), TimerMode::Once);
+
+        let solid = |c: &GridCoords| lookup.get(c).is_some_and(|e| solid_q.contains(*e));
+        let enemy = |c: &GridCoords| lookup.get(c).is_some_and(|e| other_enemy_q.contains(*e));
+        let below = coords.down();
+        let target = if below == *coords {
+            // fell through the bottom of the level
+            lookup.leave(e, &coords);
+            cmd.entity(e).despawn_recursive();
+            continue;
+        } else if !solid(&below) {
+            // nothing to stand on, land on the enemy below once it moves away
+            if enemy(&below) {
+                continue;
+            }
+            below
+        } else {
+            if let Some(player) = player.filter(|_| chasing) {
+                if player.x != coords.x {
+                    ai.dir = (player.x - coords.x).signum();
+                }
+            }
+            let next = GridCoords::new(coords.x + ai.dir, coords.y);
+            let blocked = solid(&next) || !solid(&next.down()) || enemy(&next);
+            if blocked {
+                // wait for the player instead of turning around
+                if !chasing {
+                    ai.dir = -ai.dir;
+                }
+                continue;
+            }
+            next
+        };
+
+        sprite.flip_x = ai.dir < 0;
+        movable.tween_duration_ms = step_ms / 2;
+        lookup.upsert(e, &coords, target);
+        *coords = target;
+    }
+}
+
+/// Stop typing at the floating words that have walked out of the range.
+fn release_distant_floating_words(
+    target_q: Query<(Entity, &GridCoords), (With<FloatingWord>, With<TypingTarget>)>,
+    player_q: Query<&GridCoords, With<Player>>,
+    mut cmd: Commands,
+) {
+    let player = or_return_quiet!(player_q.get_single());
+    for (e, coords) in &target_q {
+        if coords.distance(player) > FLOATING_WORD_RANGE {
+            cmd.entity(e).remove::<TypingTarget>();
+        }
+    }
+}
+
+fn damage_player_on_contact(
+    player_q: Query<(Entity, &Transform, &KinematicSensor), With<Player>>,
+    cast: SpatialQuery,
+    mut damage_evw: EventWriter<DamagePlayer>,
+) {
+    let (e, t, sensor) = or_return_quiet!(player_q.get_single());
+    let touching = cast.shape_intersections(
+        &Collider::rectangle(sensor.size.x, sensor.size.y),
+        t.translation.truncate(),
+        0.,
+        SpatialQueryFilter {
+            mask: GamePhysicsLayer::Enemy.into(),
+            excluded_entities: [e].into(),
+        },
+    );
+    if !touching.is_empty() {
+        damage_evw.send(DamagePlayer(CONTACT_DAMAGE));
+    }
+}
+
+fn defeat_enemies(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    enemy_q: Query<&WordTile, With<Enemy>>,
+    mut cmd: Commands,
+) {
+    for ev in word_tile_evr
+        .read()
+        .filter(|ev| matches!(ev.kind, WordTileEventKind::TileFinished { .. }))
+    {
+        let word = or_continue_quiet!(enemy_q.get(ev.e));
+        cmd.tween_text_alpha(word.text_e(), 0.0, 110, EaseFunction::QuadraticIn);
+        let mut e_cmd = or_continue!(cmd.get_entity(ev.e));
+        e_cmd
+            .remove::<(Enemy, EnemyAi, Collider)>()
+            .try_insert(DespawnOnTweenCompleted::Itself);
+        cmd.tween_sprite_color(ev.e, Color::NONE, 200, EaseFunction::QuadraticIn);
+    }
+}
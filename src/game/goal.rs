@@ -1,9 +1,13 @@
-use crate::prelude::*;
+use crate::{
+    game::{campaign::CampaignProgress, replay::ReplayPlayback},
+    prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<GoalBundle>("Goal")
         .init_resource::<LevelIndex>()
         .register_type::<LevelIndex>()
+        .add_event::<LevelCompleted>()
         .add_systems(OnEnter(Screen::Game), update_level_selection)
         .add_systems(Update, check_goal_reached.run_if(level_ready));
 }
@@ -20,9 +24,15 @@ struct GoalBundle {
     grid_coords: GridCoords,
 }
 
+/// The player has reached the goal of the level.
+#[derive(Event, Debug)]
+pub(crate) struct LevelCompleted {
+    pub iid: String,
+}
+
 #[derive(Resource, Debug, Reflect)]
 #[reflect(Resource)]
-struct LevelIndex(usize);
+pub(crate) struct LevelIndex(pub usize);
 
 #[allow(clippy::derivable_impls)]
 impl Default for LevelIndex {
@@ -39,13 +49,22 @@ fn update_level_selection(lvl_index: Res<LevelIndex>, mut selected_lvl: ResMut<L
 fn check_goal_reached(
     goal_q: Query<&GridCoords, With<Goal>>,
     player_q: Query<&GridCoords, (With<Player>, Changed<GridCoords>)>,
-    mut lvl_index: ResMut<LevelIndex>,
+    level_iid_q: Query<&LevelIid>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut progress: ResMut<CampaignProgress>,
+    mut completed_evw: EventWriter<LevelCompleted>,
     mut cmd: Commands,
 ) {
     let player_coords = or_return_quiet!(player_q.get_single());
     let goal_coords = or_return_quiet!(goal_q.get_single());
     if player_coords == goal_coords {
-        lvl_index.0 += 1;
-        cmd.transition_to_screen(Screen::RestartGame);
+        // replays, possibly of someone else's run, don't count towards the campaign
+        if let (Ok(level_iid), None) = (level_iid_q.get_single(), playback) {
+            progress.complete(level_iid.get());
+            completed_evw.send(LevelCompleted {
+                iid: level_iid.get().clone(),
+            });
+        }
+        cmd.transition_to_screen(Screen::Score);
     }
 }
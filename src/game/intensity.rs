@@ -0,0 +1,93 @@
+//! Gameplay intensity driving the layers of the game music.
+
+use crate::{game::goal::Goal, prelude::*};
+
+/// Typed chars per second that count as full intensity.
+const FULL_TYPING_RATE: f32 = 5.;
+/// How quickly the typing rate forgets the old input, in seconds.
+const TYPING_RATE_WINDOW: f32 = 2.;
+/// Chars typed without a mistake that count as full intensity.
+const FULL_COMBO: usize = 40;
+/// The combo is lost when nothing gets typed for this long, in seconds.
+const COMBO_TIMEOUT: f32 = 2.5;
+/// Distance from the goal in tiles where the proximity starts to count.
+const GOAL_PROXIMITY_RANGE: f32 = 20.;
+/// How fast the intensity follows the gameplay.
+const INTENSITY_SMOOTHING: f32 = 2.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TypingStats>()
+        .init_resource::<TypingStats>()
+        .add_systems(OnEnter(Screen::Game), reset_intensity)
+        .add_systems(
+            Update,
+            (collect_typing_stats, update_music_intensity)
+                .chain()
+                .run_if(level_ready),
+        );
+}
+
+/// Recent typing performance of the player.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct TypingStats {
+    /// Exponentially decaying typed chars per second.
+    pub rate: f32,
+    /// Chars typed since the last mistake.
+    pub combo: usize,
+    /// Seconds since the last typed char.
+    idle: f32,
+}
+
+fn reset_intensity(mut stats: ResMut<TypingStats>, mut intensity: ResMut<MusicIntensity>) {
+    *stats = default();
+    intensity.0 = 0.;
+}
+
+fn collect_typing_stats(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    mut stats: ResMut<TypingStats>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut typed = 0;
+    for ev in word_tile_evr.read() {
+        match ev.kind {
+            WordTileEventKind::WordStarted
+            | WordTileEventKind::WordAdvanced
+            | WordTileEventKind::WordFinished(_)
+            | WordTileEventKind::TileFinished { .. } => typed += 1,
+            WordTileEventKind::Mistyped => stats.combo = 0,
+            WordTileEventKind::Decayed { .. } | WordTileEventKind::Replaced => {}
+        }
+    }
+
+    stats.rate *= (-dt / TYPING_RATE_WINDOW).exp();
+    stats.rate += typed as f32 / TYPING_RATE_WINDOW;
+    if typed > 0 {
+        stats.combo += typed;
+        stats.idle = 0.;
+    } else {
+        stats.idle += dt;
+        if stats.idle > COMBO_TIMEOUT {
+            stats.combo = 0;
+        }
+    }
+}
+
+fn update_music_intensity(
+    stats: Res<TypingStats>,
+    player_q: Query<&GridCoords, With<Player>>,
+    goal_q: Query<&GridCoords, With<Goal>>,
+    mut intensity: ResMut<MusicIntensity>,
+    time: Res<Time>,
+) {
+    let proximity = match (player_q.get_single(), goal_q.get_single()) {
+        (Ok(player), Ok(goal)) => 1. - (player.distance(goal) / GOAL_PROXIMITY_RANGE).min(1.),
+        _ => 0.,
+    };
+    let target = (stats.rate / FULL_TYPING_RATE).min(1.) * 0.5
+        + (stats.combo as f32 / FULL_COMBO as f32).min(1.) * 0.25
+        + proximity * 0.25;
+    intensity.0 += (target - intensity.0) * (INTENSITY_SMOOTHING * time.delta_seconds()).min(1.);
+}
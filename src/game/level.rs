@@ -1,18 +1,39 @@
-use crate::prelude::*;
+use crate::{game::word_tier::WordTier, prelude::*};
+use bevy::color::palettes::tailwind;
 
 pub(crate) const TILE_SIZE: u32 = 32;
 
+// IntGrid values of the `Ground` layer
+pub(crate) const UNBREAKABLE_GROUND_VALUE: i32 = 1;
+pub(crate) const GROUND_VALUE: i32 = 2;
+pub(crate) const DIRT_VALUE: i32 = 4;
+pub(crate) const STONE_VALUE: i32 = 5;
+pub(crate) const ORE_VALUE: i32 = 6;
+/// Health restored by every mined ore.
+const ORE_HEAL: u32 = 1;
+
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(LdtkPlugin)
-        .register_ldtk_int_cell::<UnbreakableGroundBundle>(1)
-        .register_ldtk_int_cell::<GroundBundle>(2)
+        .register_ldtk_int_cell::<UnbreakableGroundBundle>(UNBREAKABLE_GROUND_VALUE)
+        .register_ldtk_int_cell::<GroundBundle>(GROUND_VALUE)
+        .register_ldtk_int_cell::<GroundBundle>(DIRT_VALUE)
+        .register_ldtk_int_cell::<GroundBundle>(STONE_VALUE)
+        .register_ldtk_int_cell::<GroundBundle>(ORE_VALUE)
         .insert_resource(LevelSelection::index(0))
         .register_type::<LevelEntityLookup>()
+        .register_type::<TileHardness>()
+        .register_type::<CollectedOre>()
+        .init_resource::<CollectedOre>()
         .add_systems(
             Update,
             (cache_level_entities, tween_entity_movement).run_if(in_game),
         )
-        .add_systems(Last, (remove_tile_from_cache,).run_if(level_ready))
+        .add_systems(
+            Last,
+            (collect_ore, remove_tile_from_cache)
+                .chain()
+                .run_if(level_ready),
+        )
         .add_systems(Update, tick_cooldown::<Moving>)
         .add_systems(OnEnter(Screen::Game), spawn_level)
         .add_systems(OnExit(Screen::Game), teardown_level);
@@ -22,13 +43,48 @@ pub(crate) fn level_ready(lookup: Option<Res<LevelEntityLookup>>) -> bool {
     lookup.is_some()
 }
 
-#[derive(Resource, Deref, DerefMut, Reflect)]
+/// Entity on top of each cell, e.g. a rock that rolled onto a pressure plate.
+#[derive(Resource, Deref, Reflect, Default)]
 #[reflect(Resource)]
-pub(crate) struct LevelEntityLookup(pub HashMap<GridCoords, Entity>);
+pub(crate) struct LevelEntityLookup {
+    #[deref]
+    cells: HashMap<GridCoords, Entity>,
+    /// Entities under the ones on top, uncovered in order once the cell is left.
+    covered: HashMap<GridCoords, Vec<Entity>>,
+}
+
 impl LevelEntityLookup {
+    /// Put the entity on top of the cell, covering whatever is there.
+    pub fn occupy(&mut self, entity: Entity, coords: GridCoords) {
+        match self.cells.insert(coords, entity) {
+            Some(previous) if previous != entity => {
+                self.covered.entry(coords).or_default().push(previous)
+            }
+            _ => {}
+        }
+    }
+
+    /// Take the entity out of the cell, uncovering the one below it.
+    pub fn leave(&mut self, entity: Entity, coords: &GridCoords) {
+        if self.cells.get(coords) != Some(&entity) {
+            if let Some(covered) = self.covered.get_mut(coords) {
+                covered.retain(|e| *e != entity);
+            }
+            return;
+        }
+        match self.covered.get_mut(coords).and_then(Vec::pop) {
+            Some(uncovered) => {
+                self.cells.insert(*coords, uncovered);
+            }
+            None => {
+                self.cells.remove(coords);
+            }
+        }
+    }
+
     pub fn upsert(&mut self, entity: Entity, old: &GridCoords, new: GridCoords) {
-        self.remove(old);
-        self.insert(new, entity);
+        self.leave(entity, old);
+        self.occupy(entity, new);
     }
 }
 
@@ -54,9 +110,59 @@ pub struct Moving;
 #[derive(Component, Default)]
 pub(crate) struct Ground;
 
+/// How much effort it takes to mine a [`Ground`] tile.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub(crate) struct TileHardness {
+    pub word_count: usize,
+    /// Tier used instead of the level one.
+    pub tier: Option<WordTier>,
+    /// Ore collected when the tile is mined.
+    pub ore: u32,
+}
+
+impl Default for TileHardness {
+    fn default() -> Self {
+        Self {
+            word_count: 3,
+            tier: None,
+            ore: 0,
+        }
+    }
+}
+
+impl TileHardness {
+    fn from_int_grid_value(value: i32) -> Self {
+        match value {
+            DIRT_VALUE => Self {
+                word_count: 1,
+                tier: Some(WordTier::Easy),
+                ..default()
+            },
+            STONE_VALUE => Self {
+                tier: Some(WordTier::Hard),
+                ..default()
+            },
+            ORE_VALUE => Self {
+                word_count: 5,
+                ore: 1,
+                ..default()
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Ore mined in the current level, each one heals the player.
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct CollectedOre(pub u32);
+
 #[derive(Default, Bundle, LdtkIntCell)]
 struct GroundBundle {
     ground: Ground,
+    #[with(tile_hardness)]
+    hardness: TileHardness,
     // todo: add collider layers
     collider: TileCollider,
     #[with(collision_layers)]
@@ -78,11 +184,16 @@ fn collision_layers(_: IntGridCell) -> CollisionLayers {
     GamePhysicsLayer::obstacle_collision_layers()
 }
 
-fn spawn_level(ass: Res<AssetServer>, mut cmd: Commands) {
+fn tile_hardness(cell: IntGridCell) -> TileHardness {
+    TileHardness::from_int_grid_value(cell.value)
+}
+
+fn spawn_level(levels: Res<LevelAssets>, mut cmd: Commands) {
+    cmd.insert_resource(CollectedOre::default());
     cmd.spawn((
         Name::new("ldtk_world"),
         LdtkWorldBundle {
-            ldtk_handle: ass.load("levels.ldtk"),
+            ldtk_handle: levels.project.clone(),
             ..Default::default()
         },
         StateScoped(Screen::Game),
@@ -98,7 +209,7 @@ fn cache_level_entities(
     mut level_evr: EventReader<LevelEvent>,
     // ldtk_project_entities: Query<&Handle<LdtkProject>>,
     // ldtk_project_assets: Res<Assets<LdtkProject>>,
-    tilemap_id_q: Query<(&GridCoords, Entity)>,
+    tilemap_id_q: Query<(Entity, &GridCoords)>,
     mut cmd: Commands,
 ) {
     for level_event in level_evr.read() {
@@ -110,35 +221,63 @@ fn cache_level_entities(
             //     .get_raw_level_by_iid(level_iid.get())
             //     .expect("spawned level should exist in project");
 
-            let coords_entity_lookup = tilemap_id_q
-                .iter()
-                .map(|(coords, e)| (*coords, e))
-                .collect();
-            cmd.insert_resource(LevelEntityLookup(coords_entity_lookup));
+            let mut lookup = LevelEntityLookup::default();
+            for (e, coords) in &tilemap_id_q {
+                lookup.occupy(e, *coords);
+            }
+            cmd.insert_resource(lookup);
         }
     }
 }
 
+fn collect_ore(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    hardness_q: Query<&TileHardness>,
+    mut player_q: Query<(Entity, &mut Health), With<Player>>,
+    mut collected: ResMut<CollectedOre>,
+    mut cmd: Commands,
+) {
+    for ev in word_tile_evr
+        .read()
+        .filter(|ev| matches!(ev.kind, WordTileEventKind::TileFinished { .. }))
+    {
+        let hardness = or_continue_quiet!(hardness_q.get(ev.e));
+        or_continue_quiet!(hardness.ore > 0);
+        **collected += hardness.ore;
+
+        let (player_e, mut health) = or_continue!(player_q.get_single_mut());
+        health.current = (health.current + hardness.ore * ORE_HEAL).min(health.max);
+        cmd.tween_sprite_color_absolute(
+            player_e,
+            tailwind::GREEN_400.into(),
+            Color::WHITE,
+            400,
+            EaseFunction::QuadraticIn,
+        );
+    }
+}
+
 fn remove_tile_from_cache(
     mut word_tile_evr: EventReader<WordTileEvent>,
     mut lookup: ResMut<LevelEntityLookup>,
 ) {
-    for finished_tile_coords in word_tile_evr.read().filter_map(|ev| match ev.kind {
-        WordTileEventKind::TileFinished { coords, .. } => Some(coords),
+    for (e, finished_tile_coords) in word_tile_evr.read().filter_map(|ev| match ev.kind {
+        WordTileEventKind::TileFinished { coords, .. } => Some((ev.e, coords)),
         _ => None,
     }) {
-        lookup.remove(&finished_tile_coords);
+        lookup.leave(e, &finished_tile_coords);
     }
 }
 
 fn tween_entity_movement(
-    player_q: Query<(Entity, &GridCoords, &Movable), Changed<GridCoords>>,
+    movable_q: Query<(Entity, &GridCoords, &Movable, &Transform), Changed<GridCoords>>,
     mut cmd: Commands,
 ) {
-    for (e, coord, movable) in &player_q {
+    for (e, coord, movable, t) in &movable_q {
         cmd.tween_translation(
             e,
-            coord.to_world(),
+            // keep the layer the entity was spawned on
+            coord.to_world_with_z(t.translation.z),
             movable.tween_duration_ms,
             movable.easing.unwrap_or(EaseFunction::QuadraticInOut),
         );
@@ -148,3 +287,25 @@ fn tween_entity_movement(
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_over_an_entity_keeps_it_in_the_lookup() {
+        let (plate, rock) = (Entity::from_raw(1), Entity::from_raw(2));
+        let (left, right) = (GridCoords::new(0, 0), GridCoords::new(1, 0));
+        let mut lookup = LevelEntityLookup::default();
+        lookup.occupy(plate, right);
+        lookup.occupy(rock, left);
+
+        lookup.upsert(rock, &left, right);
+        assert_eq!(lookup.get(&right), Some(&rock));
+        assert_eq!(lookup.get(&left), None);
+
+        lookup.upsert(rock, &right, left);
+        assert_eq!(lookup.get(&right), Some(&plate));
+        assert_eq!(lookup.get(&left), Some(&rock));
+    }
+}
@@ -5,13 +5,23 @@
 
 use crate::prelude::*;
 
+pub mod boss;
+pub mod campaign;
+pub mod enemy;
 pub mod fog_of_war;
 pub mod goal;
+pub mod intensity;
 pub mod level;
 pub mod physics;
 pub mod player;
+pub mod puzzle;
+pub mod replay;
 pub mod rock;
+#[cfg(test)]
+mod solvability;
+pub mod stats;
 pub mod word;
+pub mod word_tier;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -19,8 +29,22 @@ pub(super) fn plugin(app: &mut App) {
         level::plugin,
         word::plugin,
         goal::plugin,
+        campaign::plugin,
+        intensity::plugin,
+        stats::plugin,
         rock::plugin,
+        enemy::plugin,
+        boss::plugin,
+        puzzle::plugin,
+        replay::plugin,
         fog_of_war::plugin,
         physics::plugin,
     ));
 }
+
+/// Player, level, words and physics without the rest of the game, for the headless tests.
+#[cfg(test)]
+pub(crate) fn gameplay_plugin(app: &mut App) {
+    app.add_plugins((player::plugin, level::plugin, word::plugin, physics::plugin))
+        .init_resource::<goal::LevelIndex>();
+}
@@ -30,6 +30,7 @@ pub const SKIN_WIDTH: f32 = 1.0;
 pub(crate) enum GamePhysicsLayer {
     Player,
     Obstacle,
+    Enemy,
 }
 
 impl GamePhysicsLayer {
@@ -101,6 +102,32 @@ impl Gravity {
     pub fn min_jump_velocity(&self) -> f32 {
         self.min_jump_velocity
     }
+
+    /// Lower gravity at the jump apex while the jump is held.
+    fn factor(&self, velocity_y: f32, jump_held: bool) -> f32 {
+        if jump_held && velocity_y.abs() <= (self.jump_velocity * 0.125) {
+            0.5
+        } else {
+            1.
+        }
+    }
+
+    /// Peak height of a jump with the jump held and the ticks until it's back at the starting height.
+    #[cfg(test)]
+    pub fn jump_arc(&self, initial_velocity: f32) -> (f32, u32) {
+        let delta = FIXED_UPDATE_FPS.recip();
+        let (mut velocity, mut height, mut peak, mut ticks) = (initial_velocity, 0., 0f32, 0);
+        loop {
+            velocity = (velocity + self.gravity * self.factor(velocity, true) * delta)
+                .max(self.max_fall_velocity);
+            height += velocity;
+            peak = peak.max(height);
+            ticks += 1;
+            if height <= 0. {
+                return (peak, ticks);
+            }
+        }
+    }
 }
 
 #[derive(Component, Reflect)]
@@ -317,14 +344,7 @@ pub(crate) fn apply_gravity(
         vel.y = if grounded.is_grounded() {
             0.
         } else {
-            let gravity_factor = if movement_intent.jump.state.pressed()
-                && vel.y.abs() <= (gravity.jump_velocity() * 0.125)
-            {
-                // lower gravity at jump apex
-                0.5
-            } else {
-                1.
-            };
+            let gravity_factor = gravity.factor(vel.y, movement_intent.jump.state.pressed());
             (vel.y + gravity.gravity * gravity_factor * time.delta_seconds()).max(
                 if grounded.is_sliding() {
                     gravity.max_slide_velocity
@@ -1,11 +1,14 @@
 use crate::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Update, (animate,).run_if(level_ready));
 }
 
-#[derive(Component, Debug, Default, Reflect, PartialEq, Eq)]
+#[derive(
+    Component, Debug, Default, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq,
+)]
 #[reflect(Component)]
 pub enum PlayerAnimation {
     #[default]
@@ -17,7 +20,7 @@ pub enum PlayerAnimation {
 }
 
 impl PlayerAnimation {
-    fn len(&self) -> usize {
+    pub(super) fn len(&self) -> usize {
         match self {
             PlayerAnimation::Idle => 5,
             PlayerAnimation::SwingAnticipation => 3,
@@ -38,6 +41,20 @@ impl PlayerAnimation {
         }
     }
 
+    pub(super) fn layout(&self, sprites: &SpriteAssets) -> Handle<TextureAtlasLayout> {
+        match self {
+            PlayerAnimation::Idle => sprites.idle_anim_layout.clone_weak(),
+            PlayerAnimation::SwingAnticipation => {
+                sprites.swing_anticipation_anim_layout.clone_weak()
+            }
+            PlayerAnimation::SwingAnticipationIdle => {
+                sprites.swing_anticipation_idle_anim_layout.clone_weak()
+            }
+            PlayerAnimation::Swing => sprites.swing_anim_layout.clone_weak(),
+            PlayerAnimation::SwingFast => sprites.swing_fast_anim_layout.clone_weak(),
+        }
+    }
+
     fn is_idle(&self) -> bool {
         matches!(
             self,
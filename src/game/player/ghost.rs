@@ -0,0 +1,357 @@
+//! Translucent ghost of the personal best run of the level, to race against.
+//! Every attempt is recorded and the one setting the [`LevelRecord::best_time`](crate::save::LevelRecord)
+//! of the level is stored next to the save.
+
+use super::animation::{AnimationTimer, PlayerAnimation};
+use crate::{
+    game::{
+        campaign::campaign_levels,
+        goal::{LevelCompleted, LevelIndex},
+        replay::ReplayPlayback,
+        stats::LevelStats,
+    },
+    prelude::*,
+    save::{autosave_completed_level, SaveGame},
+    storage::{self, StorageError},
+};
+use bevy::{asset::ron, color::palettes::tailwind, ui::Val::*};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const GHOST_ALPHA: f32 = 0.4;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GhostRecording>()
+        .add_systems(
+            OnEnter(Screen::Game),
+            (load_best_ghost, start_ghost_recording, spawn_split_hud).chain(),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                record_ghost_frame.run_if(not(resource_exists::<ReplayPlayback>)),
+                advance_ghost,
+            )
+                .in_set(AppSet::UpdateCoords)
+                .run_if(level_ready),
+        )
+        .add_systems(
+            Update,
+            (spawn_ghost, animate_ghost, update_split_hud).run_if(level_ready),
+        )
+        .add_systems(
+            Update,
+            store_best_ghost
+                // compares against the record before the autosave updates it
+                .before(autosave_completed_level)
+                .run_if(
+                    on_event::<LevelCompleted>().and_then(not(resource_exists::<ReplayPlayback>)),
+                ),
+        );
+}
+
+/// State of the player in a single fixed tick.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct GhostFrame {
+    x: f32,
+    y: f32,
+    flipped: bool,
+    animation: PlayerAnimation,
+    /// Chars typed since the start of the attempt.
+    chars_typed: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+struct GhostRun {
+    frames: Vec<GhostFrame>,
+}
+
+impl GhostRun {
+    fn storage_name(iid: &str) -> String {
+        format!("ghost_{iid}")
+    }
+
+    fn read(iid: &str) -> Result<Option<Self>, GhostError> {
+        let Some(stored) = storage::read(&Self::storage_name(iid))? else {
+            return Ok(None);
+        };
+        Ok(Some(ron::from_str(&stored)?))
+    }
+
+    fn save(&self, iid: &str) -> Result<(), GhostError> {
+        let serialized = ron::to_string(self)?;
+        Ok(storage::write(&Self::storage_name(iid), &serialized)?)
+    }
+
+    /// Ticks between the player and the ghost reaching `chars_typed`, positive when the player is behind.
+    fn split(&self, chars_typed: u32, tick: usize) -> Option<i64> {
+        let ghost_tick = self
+            .frames
+            .iter()
+            .position(|frame| frame.chars_typed >= chars_typed)?;
+        Some(tick as i64 - ghost_tick as i64)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum GhostError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("could not parse the ghost: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not serialize the ghost: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// Personal best of the current level, if it has been completed before.
+#[derive(Resource, Debug)]
+struct BestGhost(GhostRun);
+
+/// Frames of the current attempt, restarted whenever the level is.
+#[derive(Resource, Debug, Default)]
+struct GhostRecording {
+    run: GhostRun,
+    chars_typed: u32,
+    /// Tick at which the player typed the last char.
+    last_typed_tick: usize,
+}
+
+#[derive(Component, Debug, Default)]
+struct Ghost {
+    tick: usize,
+}
+
+/// Time difference to the ghost at the last typed char.
+#[derive(Component, Debug)]
+struct SplitText;
+
+fn load_best_ghost(
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    lvl_index: Res<LevelIndex>,
+    mut cmd: Commands,
+) {
+    cmd.remove_resource::<BestGhost>();
+    let levels = campaign_levels(&levels, &projects);
+    let level = or_return!(levels.get(lvl_index.0));
+    match GhostRun::read(&level.iid) {
+        Ok(Some(run)) => cmd.insert_resource(BestGhost(run)),
+        Ok(None) => {}
+        Err(err) => warn!("Failed to load the ghost of {}: {err}", level.name),
+    }
+}
+
+fn start_ghost_recording(mut recording: ResMut<GhostRecording>) {
+    *recording = default();
+}
+
+fn spawn_split_hud(best: Option<Res<BestGhost>>, mut cmd: Commands) {
+    or_return_quiet!(best.is_some());
+    cmd.spawn((
+        Name::new("Ghost split"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Px(10.0),
+                right: Px(10.0),
+                ..default()
+            },
+            ..default()
+        },
+        StateScoped(Screen::Game),
+    ))
+    .with_children(|children| {
+        children.spawn((
+            SplitText,
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    ..default()
+                },
+            ),
+        ));
+    });
+}
+
+fn spawn_ghost(
+    best: Option<Res<BestGhost>>,
+    ghost_q: Query<(), With<Ghost>>,
+    player_q: Query<(&Parent, &Transform), (With<Player>, With<PlayerAnimation>)>,
+    sprites: Res<SpriteAssets>,
+    mut cmd: Commands,
+) {
+    let best = or_return_quiet!(best);
+    or_return_quiet!(ghost_q.is_empty());
+    let (parent, player_t) = or_return_quiet!(player_q.get_single());
+    let first = or_return!(best.0.frames.first());
+    cmd.spawn((
+        Name::new("Ghost"),
+        Ghost::default(),
+        SpriteBundle {
+            texture: sprites.player_sheet.clone_weak(),
+            sprite: Sprite {
+                color: Color::WHITE.with_alpha(GHOST_ALPHA),
+                ..default()
+            },
+            // behind the player
+            transform: Transform::from_xyz(first.x, first.y, player_t.translation.z - 0.1),
+            ..default()
+        },
+        TextureAtlas {
+            layout: first.animation.layout(&sprites),
+            index: 0,
+        },
+        first.animation,
+        AnimationTimer(Timer::new(
+            Duration::from_millis(first.animation.frame_base_duration_ms(0)),
+            TimerMode::Repeating,
+        )),
+    ))
+    .set_parent(parent.get());
+}
+
+fn record_ghost_frame(
+    player_q: Query<(&Transform, &PlayerAnimation), With<Player>>,
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    mut recording: ResMut<GhostRecording>,
+) {
+    let (player_t, animation) = or_return!(player_q.get_single());
+    let typed = word_tile_evr
+        .read()
+        .filter(|ev| {
+            matches!(
+                ev.kind,
+                WordTileEventKind::WordStarted
+                    | WordTileEventKind::WordAdvanced
+                    | WordTileEventKind::WordFinished(_)
+                    | WordTileEventKind::TileFinished { .. }
+            )
+        })
+        .count() as u32;
+    if typed > 0 {
+        recording.chars_typed += typed;
+        recording.last_typed_tick = recording.run.frames.len();
+    }
+    let frame = GhostFrame {
+        x: player_t.translation.x,
+        y: player_t.translation.y,
+        flipped: player_t.scale.x < 0.,
+        animation: *animation,
+        chars_typed: recording.chars_typed,
+    };
+    recording.run.frames.push(frame);
+}
+
+fn advance_ghost(
+    best: Option<Res<BestGhost>>,
+    mut ghost_q: Query<(
+        &mut Ghost,
+        &mut Transform,
+        &mut PlayerAnimation,
+        &mut TextureAtlas,
+    )>,
+    sprites: Res<SpriteAssets>,
+) {
+    let best = or_return_quiet!(best);
+    for (mut ghost, mut t, mut animation, mut atlas) in &mut ghost_q {
+        // stays at the goal once the run is over
+        let frame = or_continue_quiet!(best.0.frames.get(ghost.tick));
+        ghost.tick += 1;
+        t.translation.x = frame.x;
+        t.translation.y = frame.y;
+        t.scale.x = if frame.flipped { -1. } else { 1. };
+        if *animation != frame.animation {
+            *animation = frame.animation;
+            atlas.layout = animation.layout(&sprites);
+            atlas.index = 0;
+        }
+    }
+}
+
+fn animate_ghost(
+    time: Res<Time>,
+    mut ghost_q: Query<(&mut AnimationTimer, Ref<PlayerAnimation>, &mut TextureAtlas), With<Ghost>>,
+) {
+    for (mut timer, animation, mut atlas) in &mut ghost_q {
+        if animation.is_changed() {
+            timer.set_duration(Duration::from_millis(
+                animation.frame_base_duration_ms(atlas.index),
+            ));
+            timer.reset();
+        }
+        timer.tick(time.delta());
+        if timer.just_finished() {
+            atlas.index = (atlas.index + 1) % animation.len();
+            timer.set_duration(Duration::from_millis(
+                animation.frame_base_duration_ms(atlas.index),
+            ));
+        }
+    }
+}
+
+fn update_split_hud(
+    best: Option<Res<BestGhost>>,
+    recording: Res<GhostRecording>,
+    time: Res<Time<Fixed>>,
+    mut text_q: Query<&mut Text, With<SplitText>>,
+) {
+    let best = or_return_quiet!(best);
+    let mut text = or_return_quiet!(text_q.get_single_mut());
+    let split = or_return_quiet!(best
+        .0
+        .split(recording.chars_typed, recording.last_typed_tick));
+    let secs = split as f32 * time.timestep().as_secs_f32();
+    let section = &mut text.sections[0];
+    section.value = format!("{secs:+.1}s");
+    section.style.color = if split > 0 {
+        tailwind::RED_400.into()
+    } else {
+        tailwind::GREEN_400.into()
+    };
+}
+
+fn store_best_ghost(
+    mut completed_evr: EventReader<LevelCompleted>,
+    save: Res<SaveGame>,
+    stats: Res<LevelStats>,
+    recording: Res<GhostRecording>,
+) {
+    for ev in completed_evr.read() {
+        or_continue_quiet!(save.is_best_time(&ev.iid, stats.time));
+        if let Err(err) = recording.run.save(&ev.iid) {
+            warn!("Failed to store the ghost: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(chars_typed: &[u32]) -> GhostRun {
+        GhostRun {
+            frames: chars_typed
+                .iter()
+                .map(|&chars_typed| GhostFrame {
+                    x: 0.,
+                    y: 0.,
+                    flipped: false,
+                    animation: default(),
+                    chars_typed,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn split_compares_when_the_chars_were_typed() {
+        let ghost = run(&[0, 0, 1, 1, 2, 3]);
+        assert_eq!(ghost.split(0, 0), Some(0));
+        // the ghost typed its first char at tick 2
+        assert_eq!(ghost.split(1, 4), Some(2));
+        assert_eq!(ghost.split(3, 3), Some(-2));
+        // further than the ghost has ever been
+        assert_eq!(ghost.split(4, 10), None);
+    }
+}
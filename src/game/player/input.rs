@@ -1,30 +1,42 @@
 use crate::prelude::*;
 use bevy::input::keyboard::{Key, KeyboardInput};
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use unicode_normalization::UnicodeNormalization;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<PlayerBindings>()
+        .register_type::<KeyboardLayout>()
+        .init_resource::<KeyboardLayout>()
         .add_plugins(InputManagerPlugin::<PlayerAction>::default())
         .init_resource::<ActionState<PlayerAction>>()
         .add_plugins(InputManagerPlugin::<UiAction>::default())
         .init_resource::<ActionState<UiAction>>()
         .insert_resource(UiAction::input_map())
+        .register_type::<TickInput>()
+        .init_resource::<TickInput>()
         .add_systems(
             Update,
             update_player_input_map.run_if(resource_changed::<PlayerBindings>),
         )
+        .add_systems(Update, learn_keyboard_layout.in_set(AppSet::CollectInput))
         .add_systems(
             FixedUpdate,
             (
-                collect_intent.in_set(AppSet::CollectInput),
+                (
+                    (read_live_input, collect_intent).chain(),
+                    release_target_on_move,
+                )
+                    .in_set(AppSet::CollectInput),
                 process_text_input.in_set(AppSet::Update),
             )
                 .run_if(level_ready),
-        );
+        )
+        .add_systems(Update, face_typing_target.run_if(level_ready));
 }
 
-#[derive(Resource, Reflect)]
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PlayerBindings {
     pub left: KeyCode,
     pub right: KeyCode,
@@ -43,6 +55,160 @@ impl Default for PlayerBindings {
     }
 }
 
+impl PlayerBindings {
+    pub fn keys(&self) -> [KeyCode; 3] {
+        [self.left, self.right, self.jump]
+    }
+
+    pub fn get(&self, binding: Binding) -> KeyCode {
+        match binding {
+            Binding::Left => self.left,
+            Binding::Right => self.right,
+            Binding::Jump => self.jump,
+        }
+    }
+
+    pub fn set(&mut self, binding: Binding, key: KeyCode) {
+        match binding {
+            Binding::Left => self.left = key,
+            Binding::Right => self.right = key,
+            Binding::Jump => self.jump = key,
+        }
+    }
+
+    /// Parse the QWERTY letters of the left, right and jump keys, e.g. `a,t,n`.
+    pub fn parse(keys: &str) -> Option<Self> {
+        let keys: Vec<_> = keys
+            .split(',')
+            .map(|key| {
+                let mut chars = key.trim().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => qwerty_key(c.to_ascii_lowercase()),
+                    _ => None,
+                }
+            })
+            .collect::<Option<_>>()?;
+        let [left, right, jump] = keys[..] else {
+            return None;
+        };
+        let bindings = Self { left, right, jump };
+        let has_conflict = Binding::ALL
+            .into_iter()
+            .any(|binding| bindings.conflict(binding, bindings.get(binding)).is_some());
+        (!has_conflict).then_some(bindings)
+    }
+
+    /// Other binding that already uses the key.
+    pub fn conflict(&self, binding: Binding, key: KeyCode) -> Option<Binding> {
+        Binding::ALL
+            .into_iter()
+            .find(|other| *other != binding && self.get(*other) == key)
+    }
+}
+
+/// Rebindable action of [`PlayerBindings`].
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum Binding {
+    Left,
+    Right,
+    Jump,
+}
+
+impl Binding {
+    pub const ALL: [Binding; 3] = [Binding::Left, Binding::Right, Binding::Jump];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Binding::Left => "Left",
+            Binding::Right => "Right",
+            Binding::Jump => "Jump",
+        }
+    }
+}
+
+/// Chars typed by the physical keys.
+/// There's no way to ask for the layout of the keyboard so it's learned from the pressed keys.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct KeyboardLayout(HashMap<KeyCode, char>);
+
+impl KeyboardLayout {
+    /// Char typed by the key, QWERTY is assumed for the keys that haven't been pressed yet.
+    pub fn char(&self, key: KeyCode) -> Option<char> {
+        self.0.get(&key).copied().or_else(|| qwerty_char(key))
+    }
+
+    /// Name of the key to show in the UI.
+    pub fn key_name(&self, key: KeyCode) -> String {
+        match self.char(key) {
+            Some(c) => c.to_uppercase().to_string(),
+            None => format!("{key:?}"),
+        }
+    }
+}
+
+fn qwerty_key(c: char) -> Option<KeyCode> {
+    use KeyCode::*;
+    [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+        KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ]
+    .into_iter()
+    .find(|key| qwerty_char(*key) == Some(c))
+}
+
+fn qwerty_char(key: KeyCode) -> Option<char> {
+    use KeyCode::*;
+    let c = match key {
+        KeyA => 'a',
+        KeyB => 'b',
+        KeyC => 'c',
+        KeyD => 'd',
+        KeyE => 'e',
+        KeyF => 'f',
+        KeyG => 'g',
+        KeyH => 'h',
+        KeyI => 'i',
+        KeyJ => 'j',
+        KeyK => 'k',
+        KeyL => 'l',
+        KeyM => 'm',
+        KeyN => 'n',
+        KeyO => 'o',
+        KeyP => 'p',
+        KeyQ => 'q',
+        KeyR => 'r',
+        KeyS => 's',
+        KeyT => 't',
+        KeyU => 'u',
+        KeyV => 'v',
+        KeyW => 'w',
+        KeyX => 'x',
+        KeyY => 'y',
+        KeyZ => 'z',
+        _ => return None,
+    };
+    Some(c)
+}
+
+fn learn_keyboard_layout(
+    mut evr_kbd: EventReader<KeyboardInput>,
+    mut layout: ResMut<KeyboardLayout>,
+) {
+    for ev in evr_kbd.read() {
+        let Key::Character(input) = &ev.logical_key else {
+            continue;
+        };
+        let mut chars = input.chars().flat_map(char::to_lowercase);
+        let c = or_continue_quiet!(chars.next());
+        or_continue_quiet!(chars.next().is_none() && !c.is_control());
+        // don't trigger the word list update when nothing has changed
+        if layout.0.get(&ev.key_code) != Some(&c) {
+            layout.0.insert(ev.key_code, c);
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
 pub enum PlayerAction {
     Move,
@@ -73,45 +239,202 @@ fn update_player_input_map(bindings: Res<PlayerBindings>, mut cmd: Commands) {
     cmd.insert_resource(input_map);
 }
 
-// todo: rotate player towards typed word if the current char is not found it the currently faced tile and the player hasn't started the tile yet (ignore when tile is not pristine?)
-// todo: reset tiles when player moves away from a tile (or even rotates?)
-fn process_text_input(
+/// Input of the player for a single fixed tick, either live or from a replay.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[reflect(Resource)]
+pub(crate) struct TickInput {
+    pub horizontal_movement: f32,
+    pub jump: ButtonState,
+    /// Chars typed during the tick, NFC normalized.
+    pub typed: String,
+}
+
+/// Text typed by the key event, if it's meant for the word tiles.
+fn typed_text<'a>(ev: &'a KeyboardInput, bindings: &PlayerBindings) -> Option<&'a str> {
+    let input = match &ev.logical_key {
+        Key::Character(input) => input.as_str(),
+        // boss phrases contain spaces
+        Key::Space => " ",
+        _ => return None,
+    };
+    if !(ev.state == bevy::input::ButtonState::Released || input.chars().any(|c| c.is_control())) {
+        return None;
+    }
+    // movement keys are not part of any word so they should not count as typos
+    (!bindings.keys().contains(&ev.key_code)).then_some(input)
+}
+
+pub(crate) fn read_live_input(
     mut evr_kbd: EventReader<KeyboardInput>,
-    player_q: Query<&GridCoords, With<Player>>,
-    level_lookup: Res<LevelEntityLookup>,
-    mut word_tile_q: Query<&mut WordTile>,
-    mut word_tile_evw: EventWriter<WordTileEvent>,
+    actions: PlayerInput,
+    bindings: Res<PlayerBindings>,
+    mut input: ResMut<TickInput>,
 ) {
-    let player_coords = or_return!(player_q.get_single());
     let mut typed = String::new();
     for ev in evr_kbd.read() {
-        if let Key::Character(input) = &ev.logical_key {
-            or_continue_quiet!(
-                ev.state == bevy::input::ButtonState::Released
-                    || input.chars().any(|c| c.is_control())
-            );
-            typed.push_str(&input.to_lowercase());
-        }
+        let text = or_continue_quiet!(typed_text(ev, &bindings));
+        typed.push_str(text);
     }
-    match typed.as_str() {
-        "" => return,
-        _ => {
-            for neighbour_coords in player_coords.neighbours() {
-                let neighbour_e = or_continue_quiet!(level_lookup.get(&neighbour_coords));
-                let mut word_tile = or_continue_quiet!(word_tile_q.get_mut(*neighbour_e));
-                if word_tile.remaining().starts_with(&typed) {
-                    word_tile_evw.send(WordTileEvent {
-                        e: *neighbour_e,
-                        kind: word_tile.advance(typed.len(), neighbour_coords),
-                    });
+    *input = TickInput {
+        horizontal_movement: actions.clamped_value(&PlayerAction::Move),
+        jump: actions
+            .button_data(&PlayerAction::Jump)
+            .expect("Jump mapped properly")
+            .state,
+        // word lists are NFC normalized too
+        typed: typed.nfc().collect(),
+    };
+}
+
+fn process_text_input(
+    input: Res<TickInput>,
+    player_q: Query<(&GridCoords, &Transform), With<Player>>,
+    level_lookup: Res<LevelEntityLookup>,
+    mut word_tile_q: Query<(&mut WordTile, Option<&MistypePolicy>, Has<TileLocked>)>,
+    target_q: Query<Entity, With<TypingTarget>>,
+    floating_q: Query<(Entity, &GridCoords), With<FloatingWord>>,
+    mut word_tile_evw: EventWriter<WordTileEvent>,
+    mut cmd: Commands,
+) {
+    let (player_coords, player_t) = or_return!(player_q.get_single());
+    let typed = &input.typed;
+
+    // the faced tile gets picked first when there's no target yet
+    let facing = player_t.scale.x.signum() as i32;
+    let mut neighbours = player_coords.neighbours();
+    neighbours.sort_by_key(|c| (c.x - player_coords.x != facing, c.y, c.x));
+    let mut candidates: Vec<_> = neighbours
+        .iter()
+        .filter_map(|c| level_lookup.get(c).map(|e| (*e, *c)))
+        .collect();
+    // floating words can be typed from further away, closest first
+    let mut floating: Vec<_> = floating_q
+        .iter()
+        .filter(|(e, c)| {
+            c.distance(player_coords) <= FLOATING_WORD_RANGE
+                && !candidates.iter().any(|(candidate_e, _)| candidate_e == e)
+        })
+        .map(|(e, c)| (e, *c))
+        .collect();
+    floating.sort_by(|(_, a), (_, b)| {
+        a.distance(player_coords)
+            .total_cmp(&b.distance(player_coords))
+    });
+    candidates.extend(floating);
+    let mut target = target_q.get_single().ok();
+
+    for typed_char in typed.chars() {
+        let mut accepted = false;
+        let mut rejected_tiles = Vec::new();
+        for (neighbour_e, neighbour_coords) in candidates.iter() {
+            let neighbour_e = *neighbour_e;
+            if target.is_some_and(|target_e| target_e != neighbour_e) {
+                continue;
+            }
+            let (mut word_tile, _, locked) = or_continue_quiet!(word_tile_q.get_mut(neighbour_e));
+            if locked || word_tile.status() == WordTileStatus::Finished {
+                continue;
+            }
+            // only the floating words of enemies and bosses can ask for capitals
+            let case_sensitive = floating_q.contains(neighbour_e);
+            if accepts(&word_tile.remaining(), typed_char, case_sensitive) {
+                accepted = true;
+                let kind = word_tile.advance(1, *neighbour_coords);
+                if matches!(
+                    kind,
+                    WordTileEventKind::WordFinished(_) | WordTileEventKind::TileFinished { .. }
+                ) {
+                    target = None;
+                    release_target(&mut cmd, neighbour_e);
+                } else if target.is_none() {
+                    target = Some(neighbour_e);
+                    if let Some(mut e_cmd) = cmd.get_entity(neighbour_e) {
+                        e_cmd.try_insert(TypingTarget);
+                    }
                 }
-                // todo: invalid input feedback
-                // possibly reset the current word on error?
+                word_tile_evw.send(WordTileEvent {
+                    e: neighbour_e,
+                    kind,
+                });
+                // only a single tile can accept the typed char
+                break;
+            } else {
+                rejected_tiles.push((neighbour_e, word_tile.status()));
             }
         }
+
+        if accepted {
+            continue;
+        }
+
+        // a typo only counts against the tiles the player has started
+        // or against all of the surrounding tiles if none has been started yet
+        let any_started = rejected_tiles
+            .iter()
+            .any(|(_, status)| *status == WordTileStatus::Damaged);
+        for (e, _) in rejected_tiles
+            .into_iter()
+            .filter(|(_, status)| !any_started || *status == WordTileStatus::Damaged)
+        {
+            let (mut word_tile, policy, _) = or_continue!(word_tile_q.get_mut(e));
+            let policy = policy.copied().unwrap_or_default();
+            word_tile_evw.send(WordTileEvent {
+                e,
+                kind: word_tile.mistype(policy),
+            });
+            // there's no progress left to protect
+            if policy != MistypePolicy::Ignore && target == Some(e) {
+                target = None;
+                release_target(&mut cmd, e);
+            }
+            if let MistypePolicy::Lock { duration_ms } = policy {
+                let mut e_cmd = or_continue!(cmd.get_entity(e));
+                e_cmd.try_insert((
+                    TileLocked,
+                    Cooldown::<TileLocked>::new(duration_ms).remove_component(),
+                ));
+            }
+        }
+    }
+}
+
+fn accepts(remaining: &str, typed: char, case_sensitive: bool) -> bool {
+    let Some(next) = remaining.chars().next() else {
+        return false;
+    };
+    next == typed || (!case_sensitive && next.to_lowercase().eq(typed.to_lowercase()))
+}
+
+fn release_target(cmd: &mut Commands, e: Entity) {
+    if let Some(mut e_cmd) = cmd.get_entity(e) {
+        e_cmd.remove::<TypingTarget>();
+    }
+}
+
+fn release_target_on_move(
+    player_q: Query<(), (With<Player>, Changed<GridCoords>)>,
+    target_q: Query<Entity, With<TypingTarget>>,
+    mut cmd: Commands,
+) {
+    if player_q.is_empty() {
+        return;
     }
 
-    typed.clear();
+    for e in &target_q {
+        release_target(&mut cmd, e);
+    }
+}
+
+fn face_typing_target(
+    target_q: Query<&GridCoords, Added<TypingTarget>>,
+    mut player_q: Query<(&GridCoords, &mut Transform), With<Player>>,
+) {
+    let target_coords = or_return_quiet!(target_q.get_single());
+    let (player_coords, mut t) = or_return!(player_q.get_single_mut());
+    let x_diff = target_coords.x - player_coords.x;
+    if x_diff != 0 {
+        t.scale.x = x_diff.signum() as f32;
+    }
 }
 
 #[derive(Default, Debug, Reflect)]
@@ -120,21 +443,17 @@ pub struct TimedButtonInput {
     pub last_pressed: Option<Duration>,
 }
 
-fn collect_intent(
+pub(crate) fn collect_intent(
     mut player_q: Query<&mut MovementIntent, With<Player>>,
-    input: PlayerInput,
+    input: Res<TickInput>,
     time: Res<Time>,
 ) {
     let mut intent = or_return!(player_q.get_single_mut());
-    let horizontal_movement = input.clamped_value(&PlayerAction::Move);
-    let jump_btn_data = input
-        .button_data(&PlayerAction::Jump)
-        .expect("Jump mapped properly");
     *intent = MovementIntent {
-        horizontal_movement,
+        horizontal_movement: input.horizontal_movement,
         jump: TimedButtonInput {
-            state: jump_btn_data.state,
-            last_pressed: match jump_btn_data.state {
+            state: input.jump,
+            last_pressed: match input.jump {
                 ButtonState::JustPressed => Some(Duration::ZERO),
                 _ => intent.jump.last_pressed.map(|last| last + time.delta()),
             },
@@ -180,3 +499,17 @@ impl UiAction {
 
 #[allow(dead_code)]
 pub(crate) type UiInput<'a> = Res<'a, ActionState<UiAction>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_case_sensitive_words_ask_for_capitals() {
+        assert!(accepts("kolo", 'K', false));
+        assert!(!accepts("kolo", 'K', true));
+        assert!(accepts("Dig deeper", 'D', true));
+        assert!(!accepts("Dig deeper", 'd', true));
+        assert!(!accepts("", 'a', false));
+    }
+}
@@ -1,21 +1,60 @@
 use crate::prelude::*;
+use bevy::color::palettes::tailwind;
+use bevy_trauma_shake::Shakes;
 use std::time::Duration;
 
 mod animation;
+mod ghost;
 pub mod input;
 pub mod movement;
 
+const PLAYER_HEALTH: u32 = 3;
+/// How long the player can't be hurt again after getting hurt.
+const HURT_COOLDOWN_MS: u64 = 1200;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((animation::plugin, input::plugin, movement::plugin))
-        .register_type::<Player>()
-        .register_ldtk_entity::<PlayerBundle>("Player")
-        .add_systems(Update, on_player_spawned.run_if(assets_exist));
+    app.add_plugins((
+        animation::plugin,
+        ghost::plugin,
+        input::plugin,
+        movement::plugin,
+    ))
+    .register_type::<Player>()
+    .register_type::<Health>()
+    .add_event::<DamagePlayer>()
+    .register_ldtk_entity::<PlayerBundle>("Player")
+    .add_systems(Update, on_player_spawned.run_if(assets_exist))
+    .add_systems(
+        Update,
+        (damage_player, tick_cooldown::<Invulnerable>).run_if(level_ready),
+    );
 }
 
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
 pub struct Player;
 
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Hurts the player, the level restarts once the [`Health`] runs out.
+#[derive(Event, Debug)]
+pub(crate) struct DamagePlayer(pub u32);
+
+/// Player that has been hurt recently and can't be hurt again until its [`Cooldown`] runs out.
+#[derive(Component, Debug, Default)]
+struct Invulnerable;
+
 #[derive(Default, Bundle, LdtkEntity)]
 struct PlayerBundle {
     player: Player,
@@ -55,6 +94,38 @@ fn on_player_spawned(
             MovementIntent::default(),
             MovementEasing::default(),
             GamePhysicsLayer::membership(GamePhysicsLayer::Player),
+            Health::new(PLAYER_HEALTH),
         ));
     }
 }
+
+fn damage_player(
+    mut damage_evr: EventReader<DamagePlayer>,
+    mut player_q: Query<(Entity, &mut Health, Has<Invulnerable>), With<Player>>,
+    mut shake: Shakes,
+    mut cmd: Commands,
+) {
+    let damage: u32 = damage_evr.read().map(|ev| ev.0).sum();
+    or_return_quiet!(damage > 0);
+    let (e, mut health, invulnerable) = or_return!(player_q.get_single_mut());
+    or_return_quiet!(!invulnerable && health.current > 0);
+
+    health.current = health.current.saturating_sub(damage);
+    shake.add_trauma(0.3);
+    cmd.play_sfx(Sfx::PlayerHurt);
+    if health.current == 0 {
+        cmd.transition_to_screen(Screen::RestartGame);
+        return;
+    }
+    cmd.entity(e).try_insert((
+        Invulnerable,
+        Cooldown::<Invulnerable>::new(HURT_COOLDOWN_MS).remove_component(),
+    ));
+    cmd.tween_sprite_color_absolute(
+        e,
+        tailwind::RED_400.into(),
+        Color::WHITE,
+        HURT_COOLDOWN_MS,
+        EaseFunction::QuadraticIn,
+    );
+}
@@ -6,6 +6,7 @@ use crate::{
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<MovementIntent>()
+        .add_event::<PlayerJumped>()
         .add_systems(
             FixedUpdate,
             (process_intent)
@@ -23,6 +24,12 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 pub const COYOTE_TIME_MS: usize = 90;
+/// Horizontal speed in px per second.
+pub const PLAYER_SPEED: f32 = 150.;
+/// Wall-jump velocity when holding towards the wall, the speed multiplier and the vertical velocity.
+pub const WALL_CLIMB_JUMP: Vec2 = Vec2::new(1.5, 4.5);
+/// Wall-jump velocity when not holding towards the wall.
+pub const WALL_JUMP: Vec2 = Vec2::new(1.8, 4.);
 pub const JUMP_INPUT_BUFFER_MS: usize = 80;
 
 #[derive(Component, Default, Reflect, Debug)]
@@ -41,6 +48,10 @@ impl MovementIntent {
     }
 }
 
+/// Sent for every jump, including the wall-jumps.
+#[derive(Event, Debug)]
+pub(crate) struct PlayerJumped;
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub(crate) struct MovementEasing {
@@ -65,12 +76,13 @@ fn process_intent(
         Option<&MovementEasing>,
         Option<&HorizontalObstacleDetection>,
     )>,
+    mut jump_evw: EventWriter<PlayerJumped>,
     time: Res<Time>,
 ) {
     let (mut velocity, gravity, mut intent, mut grounded, easing, horizontal_obstacles) =
         or_return!(movement_q.get_single_mut());
     // todo: component/add to controller component?
-    let speed = 150.;
+    let speed = PLAYER_SPEED;
     let target = speed * intent.horizontal_movement;
     match &easing {
         Some(easing) => velocity.x.smooth_nudge(
@@ -98,11 +110,16 @@ fn process_intent(
         && horizontal.closest_sign().is_some()
     {
         // todo: store when the horizontal collision has changed or similar to prevent further jumping until the player re-enters the collision os is grounded (or smt similar?)
-        if intent.horizontal_sign() == horizontal.closest_sign().unwrap() {
-            velocity.0 = Vec2::new(speed * 1.5 * -horizontal.closest_sign().unwrap(), 4.5);
+        jump_evw.send(PlayerJumped);
+        let wall_jump = if intent.horizontal_sign() == horizontal.closest_sign().unwrap() {
+            WALL_CLIMB_JUMP
         } else {
-            velocity.0 = Vec2::new(speed * 1.8 * -horizontal.closest_sign().unwrap(), 4.);
-        }
+            WALL_JUMP
+        };
+        velocity.0 = Vec2::new(
+            speed * wall_jump.x * -horizontal.closest_sign().unwrap(),
+            wall_jump.y,
+        );
     }
     // wall sliding
     else if let (
@@ -121,6 +138,7 @@ fn process_intent(
     {
         intent.jump.last_pressed = None;
         velocity.y = gravity.jump_velocity();
+        jump_evw.send(PlayerJumped);
         match grounded.as_mut() {
             Grounded::Grounded => *grounded = Grounded::airborne(1),
             Grounded::Airborne {
@@ -158,3 +176,64 @@ fn update_grid_coords(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness::TestApp;
+
+    #[test]
+    fn wall_jump_leaves_the_wall() {
+        let mut app = TestApp::new();
+        let player = app.player();
+
+        // run into the wall on the left and jump along it
+        app.hold(Binding::Left);
+        app.step(60);
+        assert_eq!(
+            app.world()
+                .get::<HorizontalObstacleDetection>(player)
+                .unwrap()
+                .closest_sign(),
+            Some(-1.)
+        );
+        app.hold(Binding::Jump);
+        app.step(1);
+        app.release(Binding::Jump);
+        app.step(8);
+        assert!(app.world().get::<Grounded>(player).unwrap().is_airborne());
+        let wall_x = app.world().get::<Transform>(player).unwrap().translation.x;
+
+        app.hold(Binding::Jump);
+        app.step(1);
+        assert!(app.world().get::<Velocity>(player).unwrap().x > 0.);
+        app.release(Binding::Left);
+        app.release(Binding::Jump);
+        app.step(20);
+        let x = app.world().get::<Transform>(player).unwrap().translation.x;
+        assert!(
+            x > wall_x + TILE_SIZE as f32 / 4.,
+            "{x} should be away from {wall_x}"
+        );
+    }
+
+    #[test]
+    fn wall_jump_does_not_need_a_slide() {
+        let mut app = TestApp::new();
+        let player = app.player();
+
+        app.hold(Binding::Left);
+        app.step(60);
+        app.hold(Binding::Jump);
+        app.step(1);
+        app.release(Binding::Jump);
+        app.step(4);
+        // still going up along the wall
+        assert!(app.world().get::<Velocity>(player).unwrap().y > 0.);
+        assert!(!app.world().get::<Grounded>(player).unwrap().is_sliding());
+
+        app.hold(Binding::Jump);
+        app.step(1);
+        assert!(app.world().get::<Velocity>(player).unwrap().x > 0.);
+    }
+}
@@ -0,0 +1,180 @@
+//! Puzzle elements reacting to the rocks.
+
+use crate::{game::rock::RockState, prelude::*};
+use bevy::color::palettes::tailwind;
+
+const PLATE_HEIGHT: f32 = 6.;
+const PLATE_PRESSED_HEIGHT: f32 = 3.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<PressurePlateBundle>("PressurePlate")
+        .register_ldtk_entity::<GateBundle>("Gate")
+        .register_type::<PressurePlate>()
+        .register_type::<Gate>()
+        .add_systems(
+            Update,
+            (
+                (spawn_plate_sprites, spawn_gate_sprites),
+                update_pressure_plates,
+                update_gates,
+            )
+                .chain()
+                .run_if(level_ready),
+        );
+}
+
+/// Pressed while a rock rests on it.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct PressurePlate {
+    pub channel: i32,
+    pub pressed: bool,
+}
+
+/// Open while all of the [`PressurePlate`]s with the same channel are pressed.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct Gate {
+    pub channel: i32,
+}
+
+/// The gate lets everything through.
+#[derive(Component, Debug, Default)]
+pub(crate) struct GateOpen;
+
+#[derive(Default, Bundle, LdtkEntity)]
+struct PressurePlateBundle {
+    #[with(pressure_plate)]
+    plate: PressurePlate,
+    #[grid_coords]
+    grid_coords: GridCoords,
+}
+
+#[derive(Default, Bundle, LdtkEntity)]
+struct GateBundle {
+    #[with(gate)]
+    gate: Gate,
+    collider: TileCollider,
+    #[with(collision_layers)]
+    collision_layers: CollisionLayers,
+    #[grid_coords]
+    grid_coords: GridCoords,
+}
+
+/// Read from the `Channel` int field.
+fn channel(entity_instance: &EntityInstance) -> i32 {
+    entity_instance
+        .get_int_field("Channel")
+        .copied()
+        .unwrap_or_default()
+}
+
+fn pressure_plate(entity_instance: &EntityInstance) -> PressurePlate {
+    PressurePlate {
+        channel: channel(entity_instance),
+        pressed: false,
+    }
+}
+
+fn gate(entity_instance: &EntityInstance) -> Gate {
+    Gate {
+        channel: channel(entity_instance),
+    }
+}
+
+fn collision_layers(_: &EntityInstance) -> CollisionLayers {
+    GamePhysicsLayer::obstacle_collision_layers()
+}
+
+fn plate_sprite(pressed: bool) -> Sprite {
+    let height = if pressed {
+        PLATE_PRESSED_HEIGHT
+    } else {
+        PLATE_HEIGHT
+    };
+    Sprite {
+        color: if pressed {
+            tailwind::GREEN_400
+        } else {
+            tailwind::GREEN_700
+        }
+        .into(),
+        custom_size: Some(Vec2::new(TILE_SIZE as f32 - 6., height)),
+        // sits at the bottom of the tile
+        anchor: bevy::sprite::Anchor::Custom(Vec2::new(
+            0.,
+            (TILE_SIZE as f32 / 2. - height / 2.) / height,
+        )),
+        ..default()
+    }
+}
+
+fn gate_sprite(open: bool) -> Sprite {
+    Sprite {
+        color: tailwind::SLATE_400
+            .with_alpha(if open { 0.2 } else { 1. })
+            .into(),
+        custom_size: Some(Vec2::splat(TILE_SIZE as f32)),
+        ..default()
+    }
+}
+
+fn spawn_plate_sprites(plate_q: Query<Entity, Added<PressurePlate>>, mut cmd: Commands) {
+    for e in &plate_q {
+        cmd.entity(e)
+            .try_insert((plate_sprite(false), Handle::<Image>::default()));
+    }
+}
+
+fn spawn_gate_sprites(gate_q: Query<Entity, Added<Gate>>, mut cmd: Commands) {
+    for e in &gate_q {
+        cmd.entity(e)
+            .try_insert((gate_sprite(false), Handle::<Image>::default()));
+    }
+}
+
+fn update_pressure_plates(
+    mut plate_q: Query<(&GridCoords, &mut PressurePlate, &mut Sprite)>,
+    rock_q: Query<(&GridCoords, &RockState), With<Rock>>,
+    mut cmd: Commands,
+) {
+    for (coords, mut plate, mut sprite) in &mut plate_q {
+        let pressed = rock_q
+            .iter()
+            .any(|(rock, state)| rock == coords && matches!(state, RockState::Resting));
+        if plate.pressed != pressed {
+            plate.pressed = pressed;
+            *sprite = plate_sprite(pressed);
+            cmd.play_sfx(Sfx::PlateToggled);
+        }
+    }
+}
+
+fn update_gates(
+    plate_q: Query<&PressurePlate>,
+    changed_plate_q: Query<(), Changed<PressurePlate>>,
+    mut gate_q: Query<(Entity, &Gate, &GridCoords, &mut Sprite, Has<GateOpen>)>,
+    blocker_q: Query<&GridCoords, Or<(With<Player>, With<Rock>)>>,
+    mut cmd: Commands,
+) {
+    // also retry the gates that couldn't close because something was in the way
+    or_return_quiet!(!changed_plate_q.is_empty() || gate_q.iter().any(|(.., open)| open));
+    for (e, gate, coords, mut sprite, open) in &mut gate_q {
+        let mut plates = plate_q
+            .iter()
+            .filter(|plate| plate.channel == gate.channel)
+            .peekable();
+        let should_open = plates.peek().is_some() && plates.all(|plate| plate.pressed);
+        if should_open && !open {
+            cmd.entity(e).insert(GateOpen).remove::<Collider>();
+            *sprite = gate_sprite(true);
+        } else if !should_open && open {
+            // don't trap anything inside of the gate
+            or_continue_quiet!(!blocker_q.iter().any(|blocker| blocker == coords));
+            cmd.entity(e)
+                .remove::<GateOpen>()
+                .insert(Collider::rectangle(TILE_SIZE as f32, TILE_SIZE as f32));
+            *sprite = gate_sprite(false);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Recording of the per tick input of a level and its deterministic playback.
+//! The replay of the last completed level is stored as `replay.ron`, so players can send it along with bug reports,
+//! and any replay can be watched with `--replay <path>`.
+
+use crate::{
+    cli::CliArgs,
+    game::{
+        goal::{LevelCompleted, LevelIndex},
+        player::input::{collect_intent, read_live_input, TickInput},
+    },
+    prelude::*,
+    rng::RunSeed,
+    storage::{self, StorageError},
+};
+use bevy::asset::ron;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const STORAGE_NAME: &str = "replay";
+/// Bump when the recorded input changes, older replays wouldn't play back the same.
+const REPLAY_VERSION: u32 = 2;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Recording>()
+        .add_systems(Startup, load_cli_replay)
+        .add_systems(OnEnter(Screen::MainMenu), start_pending_replay)
+        .add_systems(OnEnter(Screen::Game), start_recording)
+        .add_systems(
+            OnExit(Screen::Game),
+            stop_playback.run_if(not(in_state(Screen::Score))),
+        )
+        .add_systems(OnExit(Screen::Score), stop_playback)
+        .add_systems(
+            FixedUpdate,
+            (
+                record_tick_input.run_if(not(resource_exists::<ReplayPlayback>)),
+                feed_replay_input.run_if(resource_exists::<ReplayPlayback>),
+            )
+                .after(read_live_input)
+                .before(collect_intent)
+                .in_set(AppSet::CollectInput)
+                .run_if(level_ready),
+        )
+        .add_systems(
+            Update,
+            store_replay.run_if(
+                on_event::<LevelCompleted>().and_then(not(resource_exists::<ReplayPlayback>)),
+            ),
+        );
+}
+
+/// Input of a single attempt at a level, with everything needed to play it back.
+/// The resource is the replay of the last completed level.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub level: usize,
+    /// Run length encoded, the number of ticks with the same input.
+    pub ticks: Vec<(u32, TickInput)>,
+}
+
+impl Replay {
+    fn new(seed: u64, level: usize) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            level,
+            ticks: default(),
+        }
+    }
+
+    fn push(&mut self, input: &TickInput) {
+        match self.ticks.last_mut() {
+            Some((count, last)) if last == input => *count += 1,
+            _ => self.ticks.push((1, input.clone())),
+        }
+    }
+
+    pub fn tick_count(&self) -> u32 {
+        self.ticks.iter().map(|(count, _)| count).sum()
+    }
+
+    fn parse(stored: &str) -> Result<Self, ReplayError> {
+        let replay: Self = ron::from_str(stored)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+        Ok(replay)
+    }
+
+    fn save(&self) -> Result<(), ReplayError> {
+        let serialized = ron::to_string(self)?;
+        Ok(storage::write(STORAGE_NAME, &serialized)?)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ReplayError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("could not read the replay: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse the replay: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not serialize the replay: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("unsupported replay version {0}")]
+    Version(u32),
+}
+
+/// Input of the current attempt, restarted whenever the level is.
+#[derive(Resource, Debug, Default)]
+struct Recording(Option<Replay>);
+
+/// Replay fed instead of the live input while it exists, until the score screen of the replay is left.
+#[derive(Resource, Debug)]
+pub(crate) struct ReplayPlayback {
+    ticks: VecDeque<(u32, TickInput)>,
+    /// Seed and level of the player's own run, restored once the playback stops.
+    restore: (u64, usize),
+}
+
+impl ReplayPlayback {
+    fn new(replay: Replay, restore: (u64, usize)) -> Self {
+        Self {
+            ticks: replay.ticks.into(),
+            restore,
+        }
+    }
+
+    /// Input of the next tick, `None` once the replay has ended.
+    fn next_tick(&mut self) -> Option<TickInput> {
+        let (count, input) = self.ticks.front_mut()?;
+        let input = input.clone();
+        *count -= 1;
+        if *count == 0 {
+            self.ticks.pop_front();
+        }
+        Some(input)
+    }
+}
+
+/// Replay the level with the seed and input of the replay.
+pub(crate) fn start_playback(cmd: &mut Commands, replay: Replay) {
+    info!(
+        "Playing back {} ticks of level {} with seed {}",
+        replay.tick_count(),
+        replay.level,
+        replay.seed
+    );
+    cmd.add(move |world: &mut World| {
+        // watching another replay doesn't make it the player's run
+        let restore = match world.get_resource::<ReplayPlayback>() {
+            Some(playback) => playback.restore,
+            None => (
+                world.resource::<RunSeed>().0,
+                world.resource::<LevelIndex>().0,
+            ),
+        };
+        world.insert_resource(RunSeed(replay.seed));
+        world.insert_resource(LevelIndex(replay.level));
+        world.insert_resource(ReplayPlayback::new(replay, restore));
+    });
+    cmd.transition_to_screen(Screen::Game);
+}
+
+/// Replay passed as `--replay <path>`, played once the game has loaded.
+#[derive(Resource)]
+struct PendingReplay(Replay);
+
+#[cfg(not(target_family = "wasm"))]
+fn load_cli_replay(cli: Res<CliArgs>, mut cmd: Commands) {
+    let path = or_return_quiet!(cli.replay.as_ref());
+    match std::fs::read_to_string(path)
+        .map_err(ReplayError::from)
+        .and_then(|stored| Replay::parse(&stored))
+    {
+        Ok(replay) => cmd.insert_resource(PendingReplay(replay)),
+        Err(err) => warn!("Failed to load the replay {path}: {err}"),
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn load_cli_replay() {}
+
+fn start_pending_replay(pending: Option<Res<PendingReplay>>, mut cmd: Commands) {
+    let PendingReplay(replay) = or_return_quiet!(pending.as_deref());
+    start_playback(&mut cmd, replay.clone());
+    cmd.remove_resource::<PendingReplay>();
+}
+
+fn start_recording(
+    seed: Res<RunSeed>,
+    lvl_index: Res<LevelIndex>,
+    mut recording: ResMut<Recording>,
+) {
+    recording.0 = Some(Replay::new(seed.0, lvl_index.0));
+}
+
+fn stop_playback(playback: Option<Res<ReplayPlayback>>, mut cmd: Commands) {
+    let playback = or_return_quiet!(playback);
+    let (seed, level) = playback.restore;
+    cmd.insert_resource(RunSeed(seed));
+    cmd.insert_resource(LevelIndex(level));
+    cmd.remove_resource::<ReplayPlayback>();
+}
+
+fn record_tick_input(input: Res<TickInput>, mut recording: ResMut<Recording>) {
+    let replay = or_return_quiet!(recording.0.as_mut());
+    replay.push(&input);
+}
+
+fn feed_replay_input(mut playback: ResMut<ReplayPlayback>, mut input: ResMut<TickInput>) {
+    // stand still once the replay has ended
+    *input = playback.next_tick().unwrap_or_default();
+}
+
+fn store_replay(mut recording: ResMut<Recording>, mut cmd: Commands) {
+    let replay = or_return!(recording.0.take());
+    if let Err(err) = replay.save() {
+        warn!("Failed to store the replay: {err}");
+    }
+    cmd.insert_resource(replay);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(typed: &str) -> TickInput {
+        TickInput {
+            typed: typed.to_string(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn same_ticks_are_run_length_encoded() {
+        let mut replay = Replay::new(1, 0);
+        replay.push(&default());
+        replay.push(&default());
+        replay.push(&typed("a"));
+        replay.push(&default());
+        assert_eq!(
+            replay.ticks,
+            vec![(2, default()), (1, typed("a")), (1, default())]
+        );
+        assert_eq!(replay.tick_count(), 4);
+    }
+
+    #[test]
+    fn playback_feeds_the_recorded_ticks_in_order() {
+        let mut replay = Replay::new(1, 0);
+        let recorded = [default(), default(), typed("a"), typed("b")];
+        for input in &recorded {
+            replay.push(input);
+        }
+        let serialized = ron::to_string(&replay).unwrap();
+        let mut playback = ReplayPlayback::new(Replay::parse(&serialized).unwrap(), (0, 0));
+        let played: Vec<_> = std::iter::from_fn(|| playback.next_tick()).collect();
+        assert_eq!(played, recorded);
+    }
+
+    #[test]
+    fn other_replay_versions_are_rejected() {
+        let stored = format!(
+            "(version: {}, seed: 1, level: 0, ticks: [])",
+            REPLAY_VERSION + 1
+        );
+        assert!(matches!(
+            Replay::parse(&stored),
+            Err(ReplayError::Version(_))
+        ));
+    }
+}
@@ -1,22 +1,262 @@
-use crate::prelude::*;
+use crate::{
+    game::{
+        enemy::Enemy,
+        physics::SKIN_WIDTH,
+        puzzle::{Gate, GateOpen},
+    },
+    prelude::*,
+};
+use bevy_trauma_shake::Shakes;
+use std::time::Duration;
+
+/// How long a rock wobbles before it starts falling.
+const WOBBLE_DURATION_MS: u64 = 600;
+const WOBBLE_ANGLE: f32 = 0.12;
+const WOBBLE_SPEED: f32 = 40.;
+/// Duration of the first cell of the fall, the following ones are faster.
+const FALL_STEP_MS: u64 = 180;
+const MIN_FALL_STEP_MS: u64 = 70;
+/// Cells a rock has to fall to break the tile it lands on.
+const BREAK_FALL_HEIGHT: u32 = 2;
+/// How long the player has to walk into a rock before it moves.
+const PUSH_DELAY_MS: u64 = 150;
+const PUSH_DURATION_MS: u64 = 200;
 
-// todo: try to make rocks pushable to squish enemies?
 // todo: also allow (some) enemies to push rocks too
 pub(super) fn plugin(app: &mut App) {
-    app.register_ldtk_entity::<RockBundle>("Rock");
+    app.register_ldtk_entity::<RockBundle>("Rock")
+        .register_type::<RockState>()
+        .register_type::<RockPush>()
+        .init_resource::<RockPush>()
+        .add_systems(
+            Update,
+            (push_rocks, check_rock_support, wobble_rocks, fall_rocks)
+                .chain()
+                .run_if(level_ready),
+        );
 }
 
+/// Entities that block rocks from moving into their cell.
+pub(crate) type Solid = Or<(
+    With<Ground>,
+    With<UnbreakableGround>,
+    With<Rock>,
+    (With<Gate>, Without<GateOpen>),
+)>;
+
 #[derive(Component, Default)]
 pub(crate) struct Rock;
 
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) enum RockState {
+    #[default]
+    Resting,
+    /// Lost its support, about to fall.
+    Wobbling(Timer),
+    Falling {
+        /// Time until the rock moves to the next cell.
+        step: Timer,
+        /// Cells fallen so far.
+        height: u32,
+    },
+}
+
+impl RockState {
+    pub fn wobbling() -> Self {
+        Self::Wobbling(Timer::new(
+            Duration::from_millis(WOBBLE_DURATION_MS),
+            TimerMode::Once,
+        ))
+    }
+}
+
+/// The rock the player is currently walking into.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct RockPush(Option<(Entity, Timer)>);
+
 #[derive(Default, Bundle, LdtkEntity)]
 struct RockBundle {
     rock: Rock,
-    gravity: Gravity,
-    grounded: Grounded,
-    velocity: Velocity,
+    state: RockState,
+    movable: Movable,
+    collider: TileCollider,
+    #[with(collision_layers)]
+    collision_layers: CollisionLayers,
     #[grid_coords]
     grid_coords: GridCoords,
     #[sprite_sheet_bundle]
     sprite_sheet_bundle: LdtkSpriteSheetBundle,
 }
+
+fn collision_layers(_: &EntityInstance) -> CollisionLayers {
+    GamePhysicsLayer::obstacle_collision_layers()
+}
+
+/// Entity in the cell below the rock that keeps it from falling.
+fn support_below(
+    coords: &GridCoords,
+    lookup: &LevelEntityLookup,
+    support_q: &Query<(), Solid>,
+) -> Option<Entity> {
+    lookup
+        .get(&coords.down())
+        .copied()
+        .filter(|e| support_q.contains(*e))
+}
+
+/// Push the rock the player walks into by one cell if there's space behind it.
+fn push_rocks(
+    player_q: Query<
+        (
+            &GridCoords,
+            &MovementIntent,
+            &Grounded,
+            &HorizontalObstacleDetection,
+        ),
+        (With<Player>, Without<Rock>),
+    >,
+    mut rock_q: Query<(&mut GridCoords, &RockState, &mut Movable, Has<Moving>), With<Rock>>,
+    solid_q: Query<(), Solid>,
+    mut lookup: ResMut<LevelEntityLookup>,
+    mut push: ResMut<RockPush>,
+    time: Res<Time>,
+) {
+    let (player_coords, intent, grounded, obstacles) = or_return_quiet!(player_q.get_single());
+    let dir = intent.horizontal_sign() as i32;
+    let touching = obstacles.as_ref().is_some_and(|closest| {
+        closest.sign() as i32 == dir && closest.distance() <= SKIN_WIDTH * 2.
+    });
+    let rock_e = lookup
+        .get(&GridCoords::new(player_coords.x + dir, player_coords.y))
+        .copied()
+        .filter(|e| dir != 0 && touching && grounded.is_grounded() && rock_q.contains(*e));
+    let Some(rock_e) = rock_e else {
+        push.0 = None;
+        return;
+    };
+
+    match &mut push.0 {
+        Some((e, timer)) if *e == rock_e => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+        }
+        _ => {
+            push.0 = Some((
+                rock_e,
+                Timer::new(Duration::from_millis(PUSH_DELAY_MS), TimerMode::Once),
+            ));
+            return;
+        }
+    }
+    push.0 = None;
+
+    let (mut coords, state, mut movable, moving) = or_return!(rock_q.get_mut(rock_e));
+    or_return_quiet!(matches!(state, RockState::Resting) && !moving);
+    let target = GridCoords::new(coords.x + dir, coords.y);
+    or_return_quiet!(!lookup.get(&target).is_some_and(|e| solid_q.contains(*e)));
+
+    movable.tween_duration_ms = PUSH_DURATION_MS;
+    movable.easing = None;
+    lookup.upsert(rock_e, &coords, target);
+    *coords = target;
+}
+
+fn check_rock_support(
+    mut rock_q: Query<(&GridCoords, &mut RockState)>,
+    support_q: Query<(), Solid>,
+    lookup: Res<LevelEntityLookup>,
+) {
+    for (coords, mut state) in &mut rock_q {
+        or_continue_quiet!(matches!(*state, RockState::Resting));
+        if support_below(coords, &lookup, &support_q).is_none() {
+            *state = RockState::wobbling();
+        }
+    }
+}
+
+fn wobble_rocks(mut rock_q: Query<(&mut RockState, &mut Transform)>, time: Res<Time>) {
+    for (mut state, mut t) in &mut rock_q {
+        let RockState::Wobbling(timer) = state.as_mut() else {
+            continue;
+        };
+        if timer.tick(time.delta()).finished() {
+            t.rotation = Quat::IDENTITY;
+            *state = RockState::Falling {
+                // move right away, the wobble was the delay
+                step: Timer::new(Duration::ZERO, TimerMode::Once),
+                height: 0,
+            };
+        } else {
+            let angle = (timer.elapsed_secs() * WOBBLE_SPEED).sin() * WOBBLE_ANGLE;
+            t.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
+
+/// Move the falling rocks cell by cell until they land on something solid.
+fn fall_rocks(
+    mut rock_q: Query<(Entity, &mut RockState, &mut GridCoords, &mut Movable), With<Rock>>,
+    player_q: Query<&GridCoords, (With<Player>, Without<Rock>)>,
+    enemy_q: Query<(Entity, &GridCoords), (With<Enemy>, Without<Rock>)>,
+    support_q: Query<(), Solid>,
+    mut word_q: Query<&mut WordTile>,
+    mut lookup: ResMut<LevelEntityLookup>,
+    mut word_tile_evw: EventWriter<WordTileEvent>,
+    mut shake: Shakes,
+    mut cmd: Commands,
+    time: Res<Time>,
+) {
+    for (e, mut state, mut coords, mut movable) in &mut rock_q {
+        let RockState::Falling { step, height } = state.as_mut() else {
+            continue;
+        };
+        if !step.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let below = coords.down();
+        if player_q.iter().any(|player| *player == below) {
+            shake.add_trauma(0.4);
+            cmd.play_sfx(Sfx::RockLanded);
+            cmd.transition_to_screen(Screen::RestartGame);
+            return;
+        }
+        // squish the enemies on the way
+        for (enemy_e, _) in enemy_q.iter().filter(|(_, enemy)| **enemy == below) {
+            if let Ok(mut word) = word_q.get_mut(enemy_e) {
+                word_tile_evw.send(WordTileEvent {
+                    e: enemy_e,
+                    kind: word.finish(below),
+                });
+            }
+        }
+
+        if let Some(support_e) = support_below(&coords, &lookup, &support_q) {
+            if *height >= BREAK_FALL_HEIGHT {
+                if let Ok(mut tile) = word_q.get_mut(support_e) {
+                    word_tile_evw.send(WordTileEvent {
+                        e: support_e,
+                        kind: tile.finish(below),
+                    });
+                }
+            }
+            if *height > 0 {
+                shake.add_trauma(0.1 + 0.05 * (*height).min(3) as f32);
+                cmd.play_sfx(Sfx::RockLanded);
+            }
+            *state = RockState::Resting;
+            continue;
+        }
+
+        *height += 1;
+        let step_ms = (FALL_STEP_MS / *height as u64).max(MIN_FALL_STEP_MS);
+        *step = Timer::new(Duration::from_millis(step_ms), TimerMode::Once);
+        movable.tween_duration_ms = step_ms;
+        movable.easing = Some(EaseFunction::QuadraticIn);
+        lookup.upsert(e, &coords, below);
+        *coords = below;
+    }
+}
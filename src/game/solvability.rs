@@ -0,0 +1,331 @@
+//! Checks that the `Goal` of every level in `levels.ldtk` can be reached from the `Player` start.
+//! The character controller is reduced to moves between the cells, derived from the real jump parameters:
+//! walking, falling, jumping, climbing walls with wall-jumps and mining the [`Ground`](super::level::Ground).
+//! Wall slides only cap the fall speed and the wall-jumps don't need them (see `wall_jump_does_not_need_a_slide`),
+//! the falls aren't timed, so the slides don't reach any other cells.
+//! The model is optimistic, e.g. it ignores the gates and the enemies and lets any tile next to the player be mined,
+//! so it only catches the levels that can't be beaten at all.
+
+use super::{
+    level::{DIRT_VALUE, GROUND_VALUE, ORE_VALUE, STONE_VALUE, UNBREAKABLE_GROUND_VALUE},
+    physics::{Gravity, FIXED_UPDATE_FPS},
+    player::movement::{PLAYER_SPEED, WALL_CLIMB_JUMP},
+};
+use crate::prelude::*;
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::ldtk::{LdtkJson, Level};
+use std::collections::VecDeque;
+
+// LDtk grid coords go down
+const UP: IVec2 = IVec2::NEG_Y;
+const DOWN: IVec2 = IVec2::Y;
+const SIDES: [IVec2; 2] = [IVec2::NEG_X, IVec2::X];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    /// Solid until it's mined.
+    Minable,
+    Solid,
+}
+
+impl Cell {
+    fn from_int_grid_value(value: i32) -> Self {
+        match value {
+            UNBREAKABLE_GROUND_VALUE => Self::Solid,
+            GROUND_VALUE | DIRT_VALUE | STONE_VALUE | ORE_VALUE => Self::Minable,
+            _ => Self::Empty,
+        }
+    }
+
+    /// The player can stand on the cell.
+    fn supports(self) -> bool {
+        self != Self::Empty
+    }
+
+    /// The player can get into the cell, mining it first if needed.
+    fn passable(self) -> bool {
+        self != Self::Solid
+    }
+}
+
+#[derive(Debug)]
+struct LevelGrid {
+    width: i32,
+    cells: Vec<Cell>,
+}
+
+impl LevelGrid {
+    /// Cells outside of the level are solid.
+    fn get(&self, coords: IVec2) -> Cell {
+        let height = self.cells.len() as i32 / self.width;
+        if coords.x < 0 || coords.y < 0 || coords.x >= self.width || coords.y >= height {
+            return Cell::Solid;
+        }
+        self.cells[(coords.y * self.width + coords.x) as usize]
+    }
+}
+
+/// How far the character controller gets, in cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Reach {
+    jump_height: i32,
+    /// Cells the player can move sideways while airborne.
+    jump_distance: i32,
+    can_climb_walls: bool,
+}
+
+impl Reach {
+    fn new(gravity: &Gravity) -> Self {
+        let tile = TILE_SIZE as f32;
+        let (jump_height, airborne_ticks) = gravity.jump_arc(gravity.jump_velocity());
+        let (climb_height, _) = gravity.jump_arc(WALL_CLIMB_JUMP.y);
+        Self {
+            jump_height: (jump_height / tile) as i32,
+            jump_distance: (PLAYER_SPEED * airborne_ticks as f32 / FIXED_UPDATE_FPS / tile) as i32,
+            can_climb_walls: climb_height >= tile,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum State {
+    Standing(IVec2),
+    /// Airborne with the cells left to move sideways.
+    Airborne(IVec2, i32),
+}
+
+impl State {
+    fn new(grid: &LevelGrid, coords: IVec2, distance_left: i32) -> Self {
+        if grid.get(coords + DOWN).supports() {
+            Self::Standing(coords)
+        } else {
+            Self::Airborne(coords, distance_left)
+        }
+    }
+
+    fn coords(&self) -> IVec2 {
+        match self {
+            Self::Standing(coords) | Self::Airborne(coords, _) => *coords,
+        }
+    }
+
+    fn next(&self, grid: &LevelGrid, reach: Reach) -> Vec<Self> {
+        let mut next = Vec::new();
+        match *self {
+            Self::Standing(coords) => {
+                for side in SIDES {
+                    if grid.get(coords + side).passable() {
+                        next.push(Self::new(grid, coords + side, reach.jump_distance));
+                    }
+                }
+                // mine the tile below
+                if grid.get(coords + DOWN).passable() {
+                    next.push(Self::new(grid, coords + DOWN, 0));
+                }
+                for height in 1..=reach.jump_height {
+                    let jump_coords = coords + UP * height;
+                    if !grid.get(jump_coords).passable() {
+                        break;
+                    }
+                    next.push(Self::Airborne(jump_coords, reach.jump_distance));
+                }
+            }
+            Self::Airborne(coords, distance_left) => {
+                if grid.get(coords + DOWN).passable() {
+                    next.push(Self::new(grid, coords + DOWN, distance_left));
+                }
+                if distance_left > 0 {
+                    for side in SIDES {
+                        if grid.get(coords + side).passable() {
+                            next.push(Self::new(grid, coords + side, distance_left - 1));
+                        }
+                    }
+                }
+                let next_to_wall = SIDES.iter().any(|side| grid.get(coords + *side).supports());
+                if reach.can_climb_walls && next_to_wall && grid.get(coords + UP).passable() {
+                    next.push(Self::Airborne(coords + UP, reach.jump_distance));
+                }
+            }
+        }
+        next
+    }
+}
+
+fn is_reachable(grid: &LevelGrid, reach: Reach, start: IVec2, goal: IVec2) -> bool {
+    let start = State::new(grid, start, 0);
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        if state.coords() == goal {
+            return true;
+        }
+        for next in state.next(grid, reach) {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+/// Cells of the level with the player start and the goal.
+#[derive(Debug)]
+struct LevelLayout {
+    grid: LevelGrid,
+    start: Option<IVec2>,
+    goal: Option<IVec2>,
+}
+
+impl LevelLayout {
+    fn from_level(level: &Level) -> Option<Self> {
+        let layers = level.layer_instances.as_ref()?;
+        let ground = layers.iter().find(|layer| layer.identifier == "Ground")?;
+        let mut layout = Self {
+            grid: LevelGrid {
+                width: ground.c_wid,
+                cells: ground
+                    .int_grid_csv
+                    .iter()
+                    .map(|value| Cell::from_int_grid_value(*value))
+                    .collect(),
+            },
+            start: None,
+            goal: None,
+        };
+        for entity in layers.iter().flat_map(|layer| &layer.entity_instances) {
+            match entity.identifier.as_str() {
+                "Player" => layout.start = Some(entity.grid),
+                "Goal" => layout.goal = Some(entity.grid),
+                // rocks can be stood on and get out of the way
+                "Rock" => {
+                    let i = (entity.grid.y * layout.grid.width + entity.grid.x) as usize;
+                    layout.grid.cells[i] = Cell::Minable;
+                }
+                _ => {}
+            }
+        }
+        Some(layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_CLIMBING: Reach = Reach {
+        jump_height: 1,
+        jump_distance: 2,
+        can_climb_walls: false,
+    };
+
+    /// `#` unbreakable, `g` ground, `P` start and `G` goal.
+    fn layout(rows: &[&str]) -> LevelLayout {
+        let mut layout = LevelLayout {
+            grid: LevelGrid {
+                width: rows[0].len() as i32,
+                cells: Vec::new(),
+            },
+            start: None,
+            goal: None,
+        };
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let coords = IVec2::new(x as i32, y as i32);
+                layout.grid.cells.push(match c {
+                    '#' => Cell::Solid,
+                    'g' => Cell::Minable,
+                    'P' => {
+                        layout.start = Some(coords);
+                        Cell::Empty
+                    }
+                    'G' => {
+                        layout.goal = Some(coords);
+                        Cell::Empty
+                    }
+                    _ => Cell::Empty,
+                });
+            }
+        }
+        layout
+    }
+
+    fn reachable(layout: &LevelLayout, reach: Reach) -> bool {
+        is_reachable(
+            &layout.grid,
+            reach,
+            layout.start.unwrap(),
+            layout.goal.unwrap(),
+        )
+    }
+
+    #[test]
+    fn reach_comes_from_the_gravity() {
+        let reach = Reach::new(&Gravity::default());
+        assert_eq!(reach.jump_height, 1);
+        assert!(reach.jump_distance >= 1);
+        assert!(reach.can_climb_walls);
+    }
+
+    #[test]
+    fn walls_can_be_climbed_out_of_pits() {
+        let pit = layout(&[
+            "#########",
+            "#P.....G#",
+            "##.....##",
+            "##.....##",
+            "#########",
+        ]);
+        assert!(!reachable(&pit, NO_CLIMBING));
+        assert!(reachable(
+            &pit,
+            Reach {
+                can_climb_walls: true,
+                ..NO_CLIMBING
+            }
+        ));
+    }
+
+    #[test]
+    fn ground_can_be_mined_through() {
+        let buried = layout(&["#####", "#P..#", "#ggg#", "#gGg#", "#####"]);
+        assert!(reachable(&buried, NO_CLIMBING));
+    }
+
+    #[test]
+    fn unbreakable_ground_blocks_the_way() {
+        let sealed = layout(&["#####", "#P..#", "#####", "#.G.#", "#####"]);
+        assert!(!reachable(&sealed, NO_CLIMBING));
+    }
+
+    #[test]
+    fn campaign_goals_are_reachable() {
+        let project: LdtkJson = serde_json::from_str(
+            &std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/levels.ldtk"))
+                .expect("Readable levels.ldtk"),
+        )
+        .expect("Valid levels.ldtk");
+        let reach = Reach::new(&Gravity::default());
+        let mut unreachable = Vec::new();
+        // every level of the project is in the campaign, so it needs both to be beaten
+        let mut incomplete = Vec::new();
+        for level in &project.levels {
+            let layout = LevelLayout::from_level(level).expect("Level with a Ground layer");
+            let (Some(start), Some(goal)) = (layout.start, layout.goal) else {
+                incomplete.push(level.identifier.clone());
+                continue;
+            };
+            if !is_reachable(&layout.grid, reach, start, goal) {
+                unreachable.push(level.identifier.clone());
+            }
+        }
+        assert!(
+            incomplete.is_empty(),
+            "There's no Player or no Goal in {incomplete:?}"
+        );
+        assert!(
+            unreachable.is_empty(),
+            "The Goal can't be reached from the Player start in {unreachable:?}"
+        );
+    }
+}
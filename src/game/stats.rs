@@ -0,0 +1,134 @@
+//! Typing statistics of the current level, shown on the score screen.
+
+use crate::{
+    game::player::{input::TickInput, movement::PlayerJumped},
+    prelude::*,
+};
+
+/// Chars that count as a single word for the WPM.
+const CHARS_PER_WORD: f32 = 5.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LevelStats>()
+        .init_resource::<LevelStats>()
+        .add_systems(OnEnter(Screen::MainMenu), reset_level_stats)
+        .add_systems(OnExit(Screen::Score), reset_level_stats)
+        .add_systems(OnEnter(Screen::RestartGame), count_death)
+        .add_systems(
+            FixedUpdate,
+            count_keystrokes.in_set(AppSet::Update).run_if(level_ready),
+        )
+        .add_systems(Update, collect_level_stats.run_if(level_ready));
+}
+
+/// Stats of the level that's being played, kept over the restarts of the level.
+#[derive(Resource, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Resource)]
+pub(crate) struct LevelStats {
+    /// Chars accepted by the words.
+    pub chars_typed: usize,
+    /// All of the typed chars, including the typos.
+    pub keystrokes: usize,
+    pub tiles_mined: usize,
+    pub jumps: usize,
+    /// Restarts of the level, by dying or by giving up.
+    pub deaths: usize,
+    /// Time spent in the level, in seconds.
+    pub time: f32,
+}
+
+impl LevelStats {
+    /// Words per minute over the whole time spent in the level.
+    pub fn wpm(&self) -> f32 {
+        if self.time <= 0. {
+            return 0.;
+        }
+        self.chars_typed as f32 / CHARS_PER_WORD / (self.time / 60.)
+    }
+
+    /// Share of the keystrokes that were accepted by the words, from 0 to 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.keystrokes == 0 {
+            return 1.;
+        }
+        (self.chars_typed as f32 / self.keystrokes as f32).min(1.)
+    }
+}
+
+/// Seconds as `m:ss`.
+pub(crate) fn format_time(secs: f32) -> String {
+    let secs = secs as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn reset_level_stats(mut stats: ResMut<LevelStats>) {
+    *stats = default();
+}
+
+fn count_death(mut stats: ResMut<LevelStats>) {
+    stats.deaths += 1;
+}
+
+fn count_keystrokes(input: Res<TickInput>, mut stats: ResMut<LevelStats>) {
+    stats.keystrokes += input.typed.chars().count();
+}
+
+fn collect_level_stats(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    mut jump_evr: EventReader<PlayerJumped>,
+    ground_q: Query<(), With<Ground>>,
+    mut stats: ResMut<LevelStats>,
+    time: Res<Time>,
+) {
+    stats.time += time.delta_seconds();
+    stats.jumps += jump_evr.read().count();
+    for ev in word_tile_evr.read() {
+        match ev.kind {
+            WordTileEventKind::WordStarted
+            | WordTileEventKind::WordAdvanced
+            | WordTileEventKind::WordFinished(_) => stats.chars_typed += 1,
+            WordTileEventKind::TileFinished { .. } => {
+                stats.chars_typed += 1;
+                if ground_q.contains(ev.e) {
+                    stats.tiles_mined += 1;
+                }
+            }
+            WordTileEventKind::Mistyped
+            | WordTileEventKind::Decayed { .. }
+            | WordTileEventKind::Replaced => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wpm_counts_five_chars_as_a_word() {
+        let stats = LevelStats {
+            chars_typed: 150,
+            time: 60.,
+            ..default()
+        };
+        assert_eq!(stats.wpm(), 30.);
+        assert_eq!(LevelStats::default().wpm(), 0.);
+    }
+
+    #[test]
+    fn accuracy_is_share_of_accepted_keystrokes() {
+        let stats = LevelStats {
+            chars_typed: 90,
+            keystrokes: 100,
+            ..default()
+        };
+        assert_eq!(stats.accuracy(), 0.9);
+        assert_eq!(LevelStats::default().accuracy(), 1.);
+    }
+
+    #[test]
+    fn time_is_formatted_as_minutes_and_seconds() {
+        assert_eq!(format_time(0.), "0:00");
+        assert_eq!(format_time(83.7), "1:23");
+    }
+}
@@ -1,18 +1,44 @@
-use crate::{assets::WordlistAssets, prelude::*};
-use bevy::{color::palettes::tailwind, utils::HashSet};
+use crate::{
+    game::word_tier::{split_into_tiers, WordTier, MAX_WORD_LEN, MIN_WORD_LEN},
+    prelude::*,
+};
+use bevy::{color::palettes::tailwind, ecs::system::EntityCommands, utils::HashSet};
 use bevy_trauma_shake::Shakes;
+use std::time::Duration;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<WordTile>()
+        .register_type::<WordTier>()
+        .register_type::<MistypePolicy>()
+        .register_type::<TileLocked>()
+        .register_type::<TypingTarget>()
+        .register_type::<FloatingWord>()
+        .register_type::<ProgressDecay>()
+        .register_type::<LevelProgressDecay>()
+        .init_resource::<LevelProgressDecay>()
+        .register_type::<WordListLanguage>()
+        .init_resource::<WordListLanguage>()
         .add_event::<WordTileEvent>()
         .add_systems(OnExit(Screen::Loading), update_word_list)
         .add_systems(
             Update,
-            update_word_list.run_if(assets_exist.and_then(resource_changed::<PlayerBindings>)),
+            update_word_list.run_if(
+                assets_exist.and_then(
+                    resource_changed::<PlayerBindings>
+                        .or_else(resource_changed::<KeyboardLayout>)
+                        .or_else(resource_changed::<WordListLanguage>)
+                        .or_else(resource_changed::<WordlistAssets>),
+                ),
+            ),
         )
         .add_systems(
             Update,
-            (spawn_tile_words, update_ground_text_sections).run_if(in_game),
+            (
+                spawn_tile_words,
+                update_ground_text_sections,
+                update_level_progress_decay,
+            )
+                .run_if(in_game),
         )
         .add_systems(
             Update,
@@ -22,16 +48,82 @@ pub(super) fn plugin(app: &mut App) {
                 shake_on_word_finished,
                 play_word_sfx,
                 spawn_cracks,
+                mistype_feedback,
+                dim_locked_tiles,
+                highlight_typing_target,
+                decay_tile_progress,
+                remove_decayed_cracks,
+                tick_cooldown::<TileLocked>,
             )
                 .run_if(level_ready),
         );
 }
 
-#[derive(Resource, Reflect, Debug, Deref, DerefMut)]
+#[derive(Resource, Reflect, Debug)]
 pub struct WordList {
-    ground_words: Vec<String>,
-    // other words for bosses etc
-    // enemy_words: Vec<String>,
+    tiers: HashMap<WordTier, Vec<String>>,
+    /// Words too long for the tiles.
+    enemy_words: Vec<String>,
+    /// Phrases with spaces and punctuation for the bosses.
+    phrases: Vec<String>,
+}
+
+/// Word count of the phrases made up from the enemy words.
+const GENERATED_PHRASE_LEN: usize = 3;
+
+/// Language code of the word list, matching the `<code>.words.txt` file name.
+#[derive(Resource, Reflect, Debug, Clone, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct WordListLanguage(pub String);
+
+impl WordListLanguage {
+    pub const FALLBACK: &'static str = "en";
+}
+
+impl Default for WordListLanguage {
+    fn default() -> Self {
+        Self(Self::FALLBACK.to_string())
+    }
+}
+
+impl WordList {
+    /// Pick random words of the given tier.
+    /// Falls back to the closest tier that has enough words.
+    pub(crate) fn choose(&self, tier: WordTier, count: usize, rng: &mut impl Rng) -> Vec<String> {
+        let mut tiers = WordTier::ALL;
+        tiers.sort_by_key(|t| (*t as i32 - tier as i32).abs());
+        tiers
+            .iter()
+            .filter_map(|t| self.tiers.get(t))
+            .find(|words| words.len() >= count)
+            .map(|words| words.choose_multiple(rng, count).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Pick random words for an enemy, falling back to the hardest tier.
+    pub(crate) fn choose_enemy(&self, count: usize, rng: &mut impl Rng) -> Vec<String> {
+        if self.enemy_words.len() >= count {
+            self.enemy_words
+                .choose_multiple(rng, count)
+                .cloned()
+                .collect()
+        } else {
+            self.choose(WordTier::Hard, count, rng)
+        }
+    }
+
+    /// Pick random phrases for a boss.
+    /// Phrases are made up from the enemy words when the language doesn't have enough of them.
+    pub(crate) fn choose_phrases(&self, count: usize, rng: &mut impl Rng) -> Vec<String> {
+        if self.phrases.len() >= count {
+            return self.phrases.choose_multiple(rng, count).cloned().collect();
+        }
+        (0..count)
+            .map(|_| self.choose_enemy(GENERATED_PHRASE_LEN, rng))
+            .filter(|words| !words.is_empty())
+            .map(|words| format!("{}.", words.join(" ")))
+            .collect()
+    }
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -39,18 +131,101 @@ pub(crate) struct WordTile {
     words: Vec<String>,
     word_i: usize,
     typed_char_len: usize,
+    mistyped: bool,
     text_e: Entity,
 }
 
+/// What happens to a [`WordTile`] when the player types a character it doesn't accept.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub(crate) enum MistypePolicy {
+    /// Keep the progress, only give feedback.
+    Ignore,
+    /// Start the current word over.
+    #[default]
+    ResetWord,
+    /// Stop accepting any input for a while.
+    Lock { duration_ms: u64 },
+}
+
+/// Tile that doesn't accept input until its [`Cooldown`] runs out.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct TileLocked;
+
+/// How a started [`WordTile`] loses its progress once the player walks away from it.
+/// Tiles without this component use the [`LevelProgressDecay`].
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub(crate) enum ProgressDecay {
+    /// Progress is kept forever.
+    #[default]
+    Keep,
+    /// The current word starts over.
+    ResetWord,
+    /// Typed chars are removed one by one, including already finished words.
+    RollBack { interval_ms: u64 },
+}
+
+impl ProgressDecay {
+    const DEFAULT_ROLL_BACK_INTERVAL_MS: u64 = 400;
+
+    /// Read from the `ProgressDecay` enum and `ProgressDecayIntervalMs` int level fields.
+    fn from_level(level: &bevy_ecs_ldtk::ldtk::Level) -> Self {
+        match level.get_enum_field("ProgressDecay").map(String::as_str) {
+            Ok("ResetWord") => Self::ResetWord,
+            Ok("RollBack") => Self::RollBack {
+                interval_ms: level
+                    .get_int_field("ProgressDecayIntervalMs")
+                    .map_or(Self::DEFAULT_ROLL_BACK_INTERVAL_MS, |ms| {
+                        (*ms).max(1) as u64
+                    }),
+            },
+            _ => Self::Keep,
+        }
+    }
+}
+
+/// [`ProgressDecay`] of the current level.
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct LevelProgressDecay(pub ProgressDecay);
+
+#[derive(Component, Deref, DerefMut)]
+struct Decaying(Timer);
+
+/// Distance in tiles a [`FloatingWord`] can be typed from.
+pub(crate) const FLOATING_WORD_RANGE: f32 = 3.;
+const FLOATING_WORD_OFFSET: f32 = TILE_SIZE as f32 * 0.75;
+
+/// [`WordTile`] of a moving entity. The text floats above it, it's always visible
+/// and it can be typed from further away than the neighbouring tiles.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct FloatingWord;
+
+/// Tile that receives all of the typed input until its current word is finished
+/// or the player moves away.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct TypingTarget;
+
 #[derive(Debug, Reflect, PartialEq, Eq)]
 pub(crate) enum WordTileEventKind {
     WordStarted,
     WordAdvanced,
     WordFinished(usize),
+    /// The words have been replaced, e.g. by the next phrase of a boss.
+    Replaced,
     TileFinished {
         word_count: usize,
         coords: GridCoords,
     },
+    Mistyped,
+    /// Progress has been lost, `word_i` is the index of the current word after the decay.
+    Decayed {
+        word_i: usize,
+    },
 }
 
 #[derive(Event, Debug, Reflect)]
@@ -72,10 +247,24 @@ impl WordTile {
             words,
             word_i: 0,
             typed_char_len: 0,
+            mistyped: false,
             text_e,
         }
     }
 
+    pub(crate) fn text_e(&self) -> Entity {
+        self.text_e
+    }
+
+    /// Start over with new words.
+    pub(crate) fn replace_words(&mut self, words: Vec<String>) -> WordTileEventKind {
+        self.words = words;
+        self.word_i = 0;
+        self.typed_char_len = 0;
+        self.mistyped = false;
+        WordTileEventKind::Replaced
+    }
+
     pub(crate) fn current_word(&self) -> &str {
         &self.words[self.word_i]
     }
@@ -90,6 +279,7 @@ impl WordTile {
     pub(crate) fn advance(&mut self, count: usize, coords: GridCoords) -> WordTileEventKind {
         let typed_len_prev = self.typed_char_len;
         self.typed_char_len += count;
+        self.mistyped = false;
         if self.typed_char_len >= self.current_word().chars().count() {
             if self.word_i < (self.words.len() - 1) {
                 self.word_i += 1;
@@ -108,11 +298,58 @@ impl WordTile {
         }
     }
 
+    /// Finish all of the remaining words at once, e.g. when the tile gets crushed.
+    pub(crate) fn finish(&mut self, coords: GridCoords) -> WordTileEventKind {
+        self.word_i = self.words.len() - 1;
+        self.typed_char_len = self.current_word().chars().count();
+        self.mistyped = false;
+        WordTileEventKind::TileFinished {
+            word_count: self.words.len(),
+            coords,
+        }
+    }
+
+    pub(crate) fn mistype(&mut self, policy: MistypePolicy) -> WordTileEventKind {
+        self.mistyped = true;
+        if policy == MistypePolicy::ResetWord {
+            self.typed_char_len = 0;
+        }
+        WordTileEventKind::Mistyped
+    }
+
+    pub(crate) fn reset_word(&mut self) -> Option<WordTileEventKind> {
+        if self.typed_char_len == 0 {
+            return None;
+        }
+        self.typed_char_len = 0;
+        self.mistyped = false;
+        Some(WordTileEventKind::Decayed {
+            word_i: self.word_i,
+        })
+    }
+
+    /// Remove the last typed char, going back to the previous word if the current one is untouched.
+    pub(crate) fn roll_back(&mut self) -> Option<WordTileEventKind> {
+        if self.typed_char_len > 0 {
+            self.typed_char_len -= 1;
+        } else if self.word_i > 0 {
+            self.word_i -= 1;
+            self.typed_char_len = self.current_word().chars().count() - 1;
+        } else {
+            return None;
+        }
+        self.mistyped = false;
+        Some(WordTileEventKind::Decayed {
+            word_i: self.word_i,
+        })
+    }
+
     pub(crate) fn status(&self) -> WordTileStatus {
         match (self.word_i, self.typed_char_len) {
             (0, 0) => WordTileStatus::Pristine,
             (word_i, typed)
-                if word_i == (self.words.len() - 1) && typed >= self.words[word_i].len() =>
+                if word_i == (self.words.len() - 1)
+                    && typed >= self.words[word_i].chars().count() =>
             {
                 WordTileStatus::Finished
             }
@@ -141,6 +378,7 @@ impl WordTile {
             self.word_i,
             self.typed_char_len,
             self.status(),
+            self.mistyped,
             alpha,
             font,
         )
@@ -150,41 +388,84 @@ impl WordTile {
 fn update_word_list(
     wordlists: Res<Assets<WordListSource>>,
     wordlist_assets: Res<WordlistAssets>,
+    language: Res<WordListLanguage>,
     bindings: Res<PlayerBindings>,
+    layout: Res<KeyboardLayout>,
     mut cmd: Commands,
 ) {
-    let blacklist: Vec<_> = [bindings.left, bindings.right, bindings.jump]
-        .into_iter()
-        .flat_map(keycode_char)
-        .map(|c| c.to_ascii_lowercase())
-        .collect();
-    let source = or_return!(wordlists.get(&wordlist_assets.en));
-    let mut words: Vec<_> = source
-        .0
+    let blacklist = binding_blacklist(&bindings, &layout);
+    let source = or_return!(current_word_list(&wordlists, &wordlist_assets, &language));
+    let untypeable: Vec<_> = source.untypeable_words(&blacklist).collect();
+    if !untypeable.is_empty() {
+        warn!(
+            "Skipping {} words containing bound keys {blacklist:?}, e.g. {:?}",
+            untypeable.len(),
+            &untypeable[..untypeable.len().min(5)]
+        );
+    }
+    let (words, enemy_words): (Vec<_>, Vec<_>) = source
+        .words
         .iter()
-        .filter(|w| w.len() >= 3 && !blacklist.iter().any(|blacklisted| w.contains(*blacklisted)))
+        .filter(|w| {
+            w.chars().count() >= MIN_WORD_LEN
+                && !blacklist.iter().any(|blacklisted| w.contains(*blacklisted))
+        })
         .cloned()
-        .collect();
-    words.sort_unstable_by_key(|w| w.len());
-    let split_i = or_return!(words.iter().enumerate().find_map(|(i, w)| if w.len() > 4 {
-        Some(i)
-    } else {
-        None
-    }));
-    let _longer_words = words.split_off(split_i);
+        .partition(|w| w.chars().count() <= MAX_WORD_LEN);
+    or_return!(!words.is_empty());
+
+    let phrases = wordlist_assets
+        .phrases(&language)
+        .and_then(|handle| wordlists.get(handle))
+        .map(|phrases| {
+            phrases
+                .words
+                .iter()
+                .filter(|p| {
+                    // capitals are typed with the same keys
+                    let p = p.to_lowercase();
+                    !blacklist.iter().any(|blacklisted| p.contains(*blacklisted))
+                })
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
 
     cmd.insert_resource(WordList {
-        ground_words: words,
+        tiers: split_into_tiers(words),
+        enemy_words,
+        phrases,
     });
 }
 
-fn keycode_char(keycode: KeyCode) -> Option<char> {
-    let formatted_keycode = format!("{:?}", keycode);
-    if formatted_keycode.starts_with("Key") {
-        formatted_keycode.chars().last()
-    } else {
-        None
-    }
+/// Chars of the bound keys, words containing them can't be typed.
+pub(crate) fn binding_blacklist(bindings: &PlayerBindings, layout: &KeyboardLayout) -> Vec<char> {
+    bindings
+        .keys()
+        .into_iter()
+        .filter_map(|key| match key {
+            // not a part of the layout, but phrases contain spaces
+            KeyCode::Space => Some(' '),
+            key => layout.char(key),
+        })
+        .collect()
+}
+
+/// Source of the selected language, falling back to [`WordListLanguage::FALLBACK`].
+pub(crate) fn current_word_list<'a>(
+    wordlists: &'a Assets<WordListSource>,
+    wordlist_assets: &WordlistAssets,
+    language: &WordListLanguage,
+) -> Option<&'a WordListSource> {
+    let handle = wordlist_assets.get(language).or_else(|| {
+        warn!(
+            "No word list for language '{}', falling back to '{}'",
+            language.0,
+            WordListLanguage::FALLBACK
+        );
+        wordlist_assets.get(WordListLanguage::FALLBACK)
+    })?;
+    wordlists.get(handle)
 }
 
 fn tile_word_text_sections(
@@ -192,15 +473,21 @@ fn tile_word_text_sections(
     word_i: usize,
     typed_len: usize,
     status: WordTileStatus,
+    mistyped: bool,
     alpha: f32,
     font: Handle<Font>,
 ) -> Vec<TextSection> {
     let mut res = Vec::with_capacity(4 + words.len());
     for (i, word) in words.iter().enumerate() {
         if i == word_i {
+            // split by chars, words can contain multi-byte chars
+            let mut chars = word.chars();
+            let typed: String = chars.by_ref().take(typed_len).collect();
+            let next_char: String = chars.next().into_iter().collect();
+            let remaining: String = chars.collect();
             if status != WordTileStatus::Pristine {
                 res.push(WordTile::section(
-                    word[..typed_len].to_string(),
+                    typed,
                     tailwind::GRAY_700.with_alpha(alpha).into(),
                     font.clone_weak(),
                 ));
@@ -211,14 +498,19 @@ fn tile_word_text_sections(
                     tailwind::GRAY_300.with_alpha(alpha).into(),
                     font.clone_weak(),
                 ));
-                let next_char_i = typed_len + 1;
                 res.push(WordTile::section(
-                    word[typed_len..next_char_i].to_string(),
-                    tailwind::GREEN_200.with_alpha(alpha).into(),
+                    next_char,
+                    (if mistyped {
+                        tailwind::RED_400
+                    } else {
+                        tailwind::GREEN_200
+                    })
+                    .with_alpha(alpha)
+                    .into(),
                     font.clone_weak(),
                 ));
                 res.push(WordTile::section(
-                    word[next_char_i..].to_string(),
+                    remaining,
                     tailwind::GRAY_200.with_alpha(alpha).into(),
                     font.clone_weak(),
                 ));
@@ -250,64 +542,154 @@ fn tile_word_text_sections(
 #[derive(Component, Default)]
 struct TileWordVisible;
 
+/// Crack sprite shown for the finished word at the given index.
+#[derive(Component)]
+struct TileCrack(usize);
+
 fn spawn_tile_words(
-    ground_q: Query<Entity, Added<Ground>>,
+    ground_q: Query<
+        (
+            Entity,
+            &GridCoords,
+            Option<&WordTier>,
+            Option<&TileHardness>,
+        ),
+        Added<Ground>,
+    >,
+    parent_q: Query<&Parent>,
+    level_iid_q: Query<&LevelIid>,
+    ldtk_project_q: Query<&Handle<LdtkProject>>,
+    ldtk_projects: Res<Assets<LdtkProject>>,
     mut cmd: Commands,
     wordlist: Res<WordList>,
     fonts: Res<FontAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = thread_rng();
-    for e in &ground_q {
-        let words: Vec<_> = wordlist
-            .ground_words
-            .choose_multiple(&mut rng, 3)
-            .cloned()
-            .collect();
+    let ldtk_project = ldtk_project_q
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_projects.get(handle));
+    // the query order isn't stable, the words of a seed should be
+    let mut tiles: Vec<_> = ground_q.iter().collect();
+    tiles.sort_by_key(|(_, coords, ..)| (coords.y, coords.x));
+    for (e, coords, tile_tier, hardness) in tiles {
+        let hardness = hardness.copied().unwrap_or_default();
+        let tile_tier = tile_tier.copied().or(hardness.tier);
+        let level = parent_q
+            .iter_ancestors(e)
+            .find_map(|parent_e| level_iid_q.get(parent_e).ok())
+            .zip(ldtk_project)
+            .and_then(|(level_iid, project)| project.get_raw_level_by_iid(level_iid.get()));
+        let tier = WordTier::for_tile(coords, tile_tier.as_ref(), level);
+        let words = wordlist.choose(tier, hardness.word_count.max(1), &mut rng.words);
+        or_continue!(!words.is_empty());
         let mut text_e = None;
         let mut e_cmd = or_continue!(cmd.get_entity(e));
         e_cmd
             .with_children(|b| {
-                text_e = Some(
-                    b.spawn((
-                        Text2dBundle {
-                            text: Text::from_sections(tile_word_text_sections(
-                                &words,
-                                0,
-                                0,
-                                WordTileStatus::Pristine,
-                                0.0,
-                                fonts.tile.clone_weak(),
-                            )),
-                            transform: Transform::from_translation(Vec2::ZERO.extend(0.1))
-                                .with_scale(Vec2::splat(0.25).extend(1.)),
-                            ..default()
-                        },
-                        HIGH_RES_RENDER_LAYER,
-                    ))
-                    .id(),
-                );
+                text_e = Some(spawn_word_text(
+                    b,
+                    &words,
+                    0.0,
+                    Vec2::ZERO.extend(0.1),
+                    fonts.tile.clone_weak(),
+                ));
             })
-            .try_insert(WordTile::new(words, text_e.unwrap()))
+            .try_insert((
+                WordTile::new(words, text_e.unwrap()),
+                MistypePolicy::default(),
+            ))
             .add_child(text_e.unwrap());
     }
 }
 
+fn spawn_word_text(
+    b: &mut ChildBuilder,
+    words: &[String],
+    alpha: f32,
+    translation: Vec3,
+    font: Handle<Font>,
+) -> Entity {
+    b.spawn((
+        Text2dBundle {
+            text: Text::from_sections(tile_word_text_sections(
+                words,
+                0,
+                0,
+                WordTileStatus::Pristine,
+                false,
+                alpha,
+                font,
+            )),
+            transform: Transform::from_translation(translation)
+                .with_scale(Vec2::splat(0.25).extend(1.)),
+            ..default()
+        },
+        HIGH_RES_RENDER_LAYER,
+    ))
+    .id()
+}
+
+/// Attach a [`FloatingWord`] to the entity.
+pub(crate) fn insert_floating_words(
+    e_cmd: &mut EntityCommands,
+    words: Vec<String>,
+    font: Handle<Font>,
+) {
+    let mut text_e = None;
+    e_cmd.with_children(|b| {
+        text_e = Some(spawn_word_text(
+            b,
+            &words,
+            1.0,
+            Vec3::new(0., FLOATING_WORD_OFFSET, 0.1),
+            font,
+        ));
+    });
+    let text_e = text_e.expect("Spawned above");
+    e_cmd.try_insert((
+        WordTile::new(words, text_e),
+        MistypePolicy::default(),
+        FloatingWord,
+        TileWordVisible,
+    ));
+}
+
 fn update_ground_text_sections(
     mut word_tile_evr: EventReader<WordTileEvent>,
-    word_q: Query<&WordTile>,
+    word_q: Query<(&WordTile, Has<TileWordVisible>)>,
     mut text_q: Query<&mut Text>,
     fonts: Res<FontAssets>,
 ) {
     for ev in word_tile_evr.read() {
-        let word = or_continue!(word_q.get(ev.e));
+        let (word, visible) = or_continue!(word_q.get(ev.e));
         let mut text = or_continue!(text_q.get_mut(word.text_e));
-        text.sections = word.text_sections(1.0, fonts.tile.clone_weak());
+        text.sections =
+            word.text_sections(if visible { 1.0 } else { 0.0 }, fonts.tile.clone_weak());
+    }
+}
+
+fn update_level_progress_decay(
+    mut level_evr: EventReader<LevelEvent>,
+    ldtk_project_q: Query<&Handle<LdtkProject>>,
+    ldtk_projects: Res<Assets<LdtkProject>>,
+    mut level_decay: ResMut<LevelProgressDecay>,
+) {
+    for level_iid in level_evr.read().filter_map(|ev| match ev {
+        LevelEvent::Spawned(level_iid) => Some(level_iid),
+        _ => None,
+    }) {
+        let ldtk_project =
+            or_continue!(ldtk_projects.get(or_continue!(ldtk_project_q.get_single())));
+        let level = or_continue!(ldtk_project.get_raw_level_by_iid(level_iid.get()));
+        **level_decay = ProgressDecay::from_level(level);
     }
 }
 
 fn tween_out_finished_tiles(
     mut word_tile_evr: EventReader<WordTileEvent>,
-    word_q: Query<&WordTile, Changed<WordTile>>,
+    // floating words are finished by their owners
+    word_q: Query<&WordTile, (Changed<WordTile>, Without<FloatingWord>)>,
     mut cmd: Commands,
 ) {
     for ev in word_tile_evr
@@ -328,9 +710,11 @@ fn tween_out_finished_tiles(
 // tween text in/out as the player approaches/leaves
 fn tween_ground_texts(
     player_q: Query<&GridCoords, (With<Player>, Changed<GridCoords>)>,
-    word_q: Query<&WordTile>,
-    visible_word_q: Query<Entity, With<TileWordVisible>>,
+    mut word_q: Query<(&mut WordTile, Option<&ProgressDecay>), Without<FloatingWord>>,
+    visible_word_q: Query<Entity, (With<TileWordVisible>, Without<FloatingWord>)>,
     level_lookup: Res<LevelEntityLookup>,
+    level_decay: Res<LevelProgressDecay>,
+    mut word_tile_evw: EventWriter<WordTileEvent>,
     mut cmd: Commands,
 ) {
     let player_coords = or_return_quiet!(player_q.get_single());
@@ -344,48 +728,107 @@ fn tween_ground_texts(
 
     // tween out when player has moved away
     for out_tile_e in visible_tile_ids.difference(&radius_tile_ids) {
-        let word = or_continue_quiet!(word_q.get(*out_tile_e));
+        let (mut word, decay) = or_continue_quiet!(word_q.get_mut(*out_tile_e));
+        let text_e = word.text_e;
         if let Some(mut cmd_e) = cmd.get_entity(*out_tile_e) {
             cmd_e.remove::<TileWordVisible>();
-            cmd.tween_text_alpha(word.text_e, 0.0, 110, EaseFunction::QuadraticOut);
+            match decay.copied().unwrap_or(**level_decay) {
+                ProgressDecay::Keep => {}
+                ProgressDecay::ResetWord => {
+                    if let Some(kind) = word.reset_word() {
+                        word_tile_evw.send(WordTileEvent {
+                            e: *out_tile_e,
+                            kind,
+                        });
+                    }
+                }
+                ProgressDecay::RollBack { interval_ms } => {
+                    if word.status() == WordTileStatus::Damaged {
+                        cmd_e.try_insert(Decaying(Timer::new(
+                            Duration::from_millis(interval_ms),
+                            TimerMode::Repeating,
+                        )));
+                    }
+                }
+            }
+            cmd.tween_text_alpha(text_e, 0.0, 110, EaseFunction::QuadraticOut);
         }
     }
 
     // tween in when player has moved in
     for tile_e in radius_tile_ids {
-        let word = or_continue_quiet!(word_q.get(tile_e));
+        let (word, _) = or_continue_quiet!(word_q.get(tile_e));
         if let Some(mut cmd_e) = cmd.get_entity(tile_e) {
-            cmd_e.try_insert(TileWordVisible);
+            cmd_e.remove::<Decaying>().try_insert(TileWordVisible);
             cmd.tween_text_alpha(word.text_e, 1.0, 110, EaseFunction::QuadraticOut);
         }
     }
 }
 
-fn play_word_sfx(mut word_tile_evr: EventReader<WordTileEvent>, mut cmd: Commands) {
-    if let Some(i) = word_tile_evr
+/// Number of crack sprites and hit sounds a tile goes through regardless of its word count.
+const DAMAGE_STAGES: usize = 3;
+
+/// 1-based damage stage of a tile with the given number of finished words.
+fn damage_stage(finished_words: usize, word_count: usize) -> usize {
+    (finished_words * DAMAGE_STAGES)
+        .div_ceil(word_count.max(1))
+        .clamp(1, DAMAGE_STAGES)
+}
+
+/// Finished word count and total word count of a finished word event of a ground tile.
+fn finished_word_count(
+    ev: &WordTileEvent,
+    word_q: &Query<&WordTile, Without<FloatingWord>>,
+) -> Option<(usize, usize)> {
+    // enemies and bosses have their own feedback
+    if !word_q.contains(ev.e) {
+        return None;
+    }
+    match ev.kind {
+        WordTileEventKind::WordFinished(i) => {
+            word_q.get(ev.e).ok().map(|word| (i, word.words.len()))
+        }
+        WordTileEventKind::TileFinished { word_count, .. } => Some((word_count, word_count)),
+        _ => None,
+    }
+}
+
+fn play_word_sfx(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    word_q: Query<&WordTile, Without<FloatingWord>>,
+    mut ducking: ResMut<MusicDucking>,
+    mut cmd: Commands,
+) {
+    if let Some((finished, word_count)) = word_tile_evr
         .read()
-        .filter_map(|ev| match ev.kind {
-            WordTileEventKind::WordFinished(i) => Some(i),
-            WordTileEventKind::TileFinished { word_count, .. } => Some(word_count),
-            _ => None,
-        })
-        .next()
+        .filter_map(|ev| finished_word_count(ev, &word_q))
+        .max_by_key(|(finished, word_count)| finished == word_count)
     {
-        word_tile_evr.clear();
-        cmd.play_sfx(Sfx::FinishWord(i));
+        cmd.play_sfx(Sfx::FinishWord(damage_stage(finished, word_count)));
+        // make room for the tile breaking
+        if finished == word_count {
+            ducking.duck();
+        }
     };
 }
 
 fn spawn_cracks(
     mut word_tile_evr: EventReader<WordTileEvent>,
+    word_q: Query<&WordTile, Without<FloatingWord>>,
     mut cmd: Commands,
     sprites: Res<SpriteAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
-    for (i, e) in word_tile_evr.read().filter_map(|ev| match ev.kind {
-        WordTileEventKind::WordFinished(i) => Some((i - 1, ev.e)),
-        WordTileEventKind::TileFinished { word_count, .. } => Some((word_count - 1, ev.e)),
-        _ => None,
-    }) {
+    for (e, (finished, word_count)) in word_tile_evr
+        .read()
+        .filter_map(|ev| finished_word_count(ev, &word_q).map(|counts| (ev.e, counts)))
+    {
+        let stage = damage_stage(finished, word_count);
+        // several words can share the same crack sprite
+        if finished > 1 && stage == damage_stage(finished - 1, word_count) {
+            continue;
+        }
+        let i = finished - 1;
         let mut e_cmd = or_continue_quiet!(cmd.get_entity(e));
         e_cmd.with_children(|b| {
             b.spawn((
@@ -394,20 +837,69 @@ fn spawn_cracks(
                     texture: sprites.tilemap.clone_weak(),
                     sprite: Sprite {
                         color: Color::NONE,
+                        // same cracks look different on the neighbouring tiles
+                        flip_x: rng.effects.gen(),
+                        flip_y: rng.effects.gen(),
                         ..default()
                     },
                     ..default()
                 },
                 TextureAtlas {
                     layout: sprites.tilemap_cracks_layout.clone_weak(),
-                    index: i,
+                    index: stage - 1,
                 },
                 sprite_color_anim(Color::WHITE, 70, EaseFunction::QuadraticOut),
+                TileCrack(i),
             ));
         });
     }
 }
 
+fn decay_tile_progress(
+    mut decaying_q: Query<(Entity, &mut WordTile, &mut Decaying)>,
+    mut word_tile_evw: EventWriter<WordTileEvent>,
+    mut cmd: Commands,
+    time: Res<Time>,
+) {
+    for (e, mut word, mut decaying) in &mut decaying_q {
+        decaying.tick(time.delta());
+        for _ in 0..decaying.times_finished_this_tick() {
+            match word.roll_back() {
+                Some(kind) => {
+                    word_tile_evw.send(WordTileEvent { e, kind });
+                }
+                None => {
+                    cmd.entity(e).remove::<Decaying>();
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn remove_decayed_cracks(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    children_q: Query<&Children>,
+    crack_q: Query<&TileCrack>,
+    mut cmd: Commands,
+) {
+    for (e, word_i) in word_tile_evr.read().filter_map(|ev| match ev.kind {
+        WordTileEventKind::Decayed { word_i } => Some((ev.e, word_i)),
+        _ => None,
+    }) {
+        let children = or_continue_quiet!(children_q.get(e));
+        for child_e in children.iter() {
+            if crack_q.get(*child_e).is_ok_and(|crack| crack.0 >= word_i) {
+                let mut e_cmd = or_continue!(cmd.get_entity(*child_e));
+                e_cmd.remove::<TileCrack>().try_insert((
+                    DespawnOnTweenCompleted::Itself,
+                    sprite_color_anim(Color::NONE, 70, EaseFunction::QuadraticIn),
+                ));
+            }
+        }
+    }
+}
+
 // todo: directional shake? (shake in the direction of the swing instead of just random)
 fn shake_on_word_finished(mut word_tile_evr: EventReader<WordTileEvent>, mut shake: Shakes) {
     if word_tile_evr
@@ -425,6 +917,61 @@ fn shake_on_word_finished(mut word_tile_evr: EventReader<WordTileEvent>, mut sha
     }
 }
 
+fn mistype_feedback(
+    mut word_tile_evr: EventReader<WordTileEvent>,
+    mut shake: Shakes,
+    mut cmd: Commands,
+) {
+    if word_tile_evr
+        .read()
+        .any(|ev| ev.kind == WordTileEventKind::Mistyped)
+    {
+        word_tile_evr.clear();
+        shake.add_trauma(0.08);
+        cmd.play_sfx_with_settings(Sfx::Mistype, PlaybackSettings::DESPAWN.with_speed(0.6));
+    }
+}
+
+fn dim_locked_tiles(
+    locked_q: Query<&WordTile, Added<TileLocked>>,
+    mut unlocked: RemovedComponents<TileLocked>,
+    visible_word_q: Query<&WordTile, With<TileWordVisible>>,
+    mut cmd: Commands,
+) {
+    for word in &locked_q {
+        cmd.tween_text_alpha(word.text_e, 0.35, 110, EaseFunction::QuadraticOut);
+    }
+
+    for e in unlocked.read() {
+        let word = or_continue_quiet!(visible_word_q.get(e));
+        cmd.tween_text_alpha(word.text_e, 1.0, 110, EaseFunction::QuadraticOut);
+    }
+}
+
+fn highlight_typing_target(
+    target_q: Query<Entity, Added<TypingTarget>>,
+    mut released: RemovedComponents<TypingTarget>,
+    word_q: Query<&WordTile>,
+    mut cmd: Commands,
+) {
+    for e in &target_q {
+        cmd.tween_tile_color(
+            e,
+            tailwind::AMBER_100.into(),
+            110,
+            EaseFunction::QuadraticOut,
+        );
+    }
+
+    for e in released.read() {
+        let word = or_continue_quiet!(word_q.get(e));
+        // finished tiles are already being tweened out
+        if word.status() != WordTileStatus::Finished {
+            cmd.tween_tile_color(e, Color::WHITE, 110, EaseFunction::QuadraticOut);
+        }
+    }
+}
+
 // // todo:
 // fn flash_on_word_finished(
 //     mut word_tile_evr: EventReader<WordTileEvent>,
@@ -441,3 +988,35 @@ fn shake_on_word_finished(mut word_tile_evr: EventReader<WordTileEvent>, mut sha
 //         cmd.tween_tile_color(ev.e, Color::NONE, 150, EaseFunction::QuadraticIn);
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness::TestApp;
+
+    #[test]
+    fn typing_three_words_removes_the_tile() {
+        let mut app = TestApp::new();
+        // right next to the player in the fixture
+        let tile_coords = GridCoords::new(3, 1);
+        let tile_e = *app
+            .world()
+            .resource::<LevelEntityLookup>()
+            .get(&tile_coords)
+            .expect("Tile in the lookup");
+        assert_eq!(app.world().get::<WordTile>(tile_e).unwrap().words.len(), 3);
+
+        for _ in 0..3 {
+            let remaining = app.world().get::<WordTile>(tile_e).unwrap().remaining();
+            app.type_text(&remaining);
+            app.step(1);
+        }
+
+        let word_tile = app.world().get::<WordTile>(tile_e).unwrap();
+        assert_eq!(word_tile.status(), WordTileStatus::Finished);
+        assert!(!app
+            .world()
+            .resource::<LevelEntityLookup>()
+            .contains_key(&tile_coords));
+    }
+}
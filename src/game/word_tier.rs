@@ -0,0 +1,170 @@
+//! Word difficulty used to split the word list into tiers.
+
+use crate::prelude::*;
+
+pub(crate) const MIN_WORD_LEN: usize = 3;
+pub(crate) const MAX_WORD_LEN: usize = 7;
+
+/// Keyboard rows used to measure how far apart the keys of a word are.
+const KEY_ROWS: [(&str, f32); 3] = [("qwertyuiop", 0.), ("asdfghjkl", 0.25), ("zxcvbnm", 0.75)];
+
+/// Difficulty of the words used by a tile.
+/// Add the component to a tile to override the tier picked by level depth.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[reflect(Component)]
+pub(crate) enum WordTier {
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl WordTier {
+    pub const ALL: [WordTier; 3] = [WordTier::Easy, WordTier::Medium, WordTier::Hard];
+
+    /// Tier based on how deep the tile is in the level,
+    /// `depth` being 0 at the top and 1 at the bottom of the level.
+    pub fn from_depth(depth: f32) -> Self {
+        match depth {
+            d if d < 1. / 3. => WordTier::Easy,
+            d if d < 2. / 3. => WordTier::Medium,
+            _ => WordTier::Hard,
+        }
+    }
+
+    /// Read from the `WordTier` enum level field.
+    pub fn from_level(level: &bevy_ecs_ldtk::ldtk::Level) -> Option<Self> {
+        match level.get_enum_field("WordTier").map(String::as_str) {
+            Ok("Easy") => Some(WordTier::Easy),
+            Ok("Medium") => Some(WordTier::Medium),
+            Ok("Hard") => Some(WordTier::Hard),
+            _ => None,
+        }
+    }
+
+    /// Tier of a tile - picked from the tile itself, then the level field and finally the depth.
+    pub fn for_tile(
+        coords: &GridCoords,
+        tile_tier: Option<&WordTier>,
+        level: Option<&bevy_ecs_ldtk::ldtk::Level>,
+    ) -> Self {
+        if let Some(tier) = tile_tier {
+            return *tier;
+        }
+        let Some(level) = level else {
+            return WordTier::default();
+        };
+        Self::from_level(level).unwrap_or_else(|| {
+            let level_height = (level.px_hei / TILE_SIZE as i32).max(1) as f32;
+            Self::from_depth(1. - (coords.y as f32 + 0.5) / level_height)
+        })
+    }
+}
+
+fn key_position(c: char) -> Option<Vec2> {
+    KEY_ROWS.iter().enumerate().find_map(|(y, (row, offset))| {
+        row.chars()
+            .position(|key| key == c)
+            .map(|x| Vec2::new(x as f32 + offset, y as f32))
+    })
+}
+
+/// Average distance between consecutive keys of the word.
+pub(crate) fn key_distance(word: &str) -> f32 {
+    let positions: Vec<_> = word.chars().filter_map(key_position).collect();
+    if positions.len() < 2 {
+        return 0.;
+    }
+    positions
+        .windows(2)
+        .map(|keys| keys[0].distance(keys[1]))
+        .sum::<f32>()
+        / (positions.len() - 1) as f32
+}
+
+/// Average rarity of the letters of the word based on how often they appear in the whole list.
+fn letter_rarity(word: &str, letter_frequency: &HashMap<char, f32>) -> f32 {
+    let char_count = word.chars().count().max(1) as f32;
+    word.chars()
+        .map(|c| {
+            -letter_frequency
+                .get(&c)
+                .copied()
+                .unwrap_or(f32::EPSILON)
+                .ln()
+        })
+        .sum::<f32>()
+        / char_count
+}
+
+/// Sort the words by difficulty and split them into equally sized tiers.
+pub(crate) fn split_into_tiers(words: Vec<String>) -> HashMap<WordTier, Vec<String>> {
+    let mut letter_counts = HashMap::<char, usize>::default();
+    for c in words.iter().flat_map(|w| w.chars()) {
+        *letter_counts.entry(c).or_default() += 1;
+    }
+    let letter_total = letter_counts.values().sum::<usize>().max(1) as f32;
+    let letter_frequency: HashMap<_, _> = letter_counts
+        .into_iter()
+        .map(|(c, count)| (c, count as f32 / letter_total))
+        .collect();
+
+    let metrics: Vec<_> = words
+        .iter()
+        .map(|w| Vec2::new(letter_rarity(w, &letter_frequency), key_distance(w)))
+        .collect();
+    let min = metrics.iter().copied().fold(Vec2::INFINITY, Vec2::min);
+    let max = metrics.iter().copied().fold(Vec2::NEG_INFINITY, Vec2::max);
+    let range = (max - min).max(Vec2::splat(f32::EPSILON));
+
+    // every extra char adds 1 while rarity and key distance add up to 1 together,
+    // so they mostly sort words of a similar length
+    let mut scored: Vec<_> = words
+        .into_iter()
+        .zip(metrics)
+        .map(|(w, metric)| {
+            let len_score = w.chars().count().saturating_sub(MIN_WORD_LEN) as f32;
+            (len_score + ((metric - min) / range).element_sum() * 0.5, w)
+        })
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| a.partial_cmp(b).expect("Valid score"));
+
+    let tier_len = scored.len().div_ceil(WordTier::ALL.len()).max(1);
+    let mut tiers = HashMap::default();
+    let mut scored = scored.into_iter();
+    for tier in WordTier::ALL {
+        tiers.insert(
+            tier,
+            scored.by_ref().take(tier_len).map(|(_, w)| w).collect(),
+        );
+    }
+    tiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("a" => 0.)]
+    #[test_case("as" => 1.)]
+    #[test_case("asdf" => 1.)]
+    #[test_case("qwer" => 1.)]
+    fn key_distance_of_word(word: &str) -> f32 {
+        key_distance(word)
+    }
+
+    #[test]
+    fn longer_words_are_harder() {
+        let tiers = split_into_tiers(
+            [
+                "cat", "dog", "sun", "table", "house", "plant", "jukebox", "quizzed", "zephyrs",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert!(tiers[&WordTier::Easy].iter().all(|w| w.len() == 3));
+        assert!(tiers[&WordTier::Medium].iter().all(|w| w.len() == 5));
+        assert!(tiers[&WordTier::Hard].iter().all(|w| w.len() == 7));
+    }
+}
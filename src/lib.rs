@@ -4,50 +4,40 @@ mod anim;
 mod assets;
 mod audio;
 mod camera;
+mod cli;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod ext;
 mod game;
 mod math;
 mod prelude;
+mod rng;
+mod save;
 mod screens;
+mod settings;
+mod storage;
+#[cfg(test)]
+mod test_harness;
 mod theme;
 mod time;
 mod tween;
 mod word_loader;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-};
+use bevy::asset::AssetMetaCheck;
+pub use cli::{CliArgs, CliError, USAGE};
 use prelude::*;
 
 const GAME_NAME: &str = "GMTK 2024";
 
-pub struct AppPlugin;
+pub struct AppPlugin {
+    /// Parsed by the binary, see [`CliArgs::from_env`].
+    pub cli: CliArgs,
+}
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(
-            FixedUpdate,
-            (
-                AppSet::TickTimers,
-                AppSet::CollectInput,
-                AppSet::Update,
-                AppSet::UpdateCoords,
-            )
-                .chain(),
-        )
-        .configure_sets(
-            Update,
-            (
-                AppSet::TickTimers,
-                AppSet::CollectInput,
-                AppSet::Update,
-                AppSet::UpdateCoords,
-            )
-                .chain(),
-        );
+        configure_app_sets(app);
+        let cli = self.cli.clone();
 
         // Add Bevy plugins.
         app.add_plugins(
@@ -62,7 +52,10 @@ impl Plugin for AppPlugin {
                 .set(WindowPlugin {
                     primary_window: Window {
                         title: GAME_NAME.to_string(),
-                        resolution: Vec2::splat(1024.).into(),
+                        resolution: cli
+                            .window
+                            .map_or(Vec2::splat(1024.), |size| size.as_vec2())
+                            .into(),
                         canvas: Some("#bevy".to_string()),
                         fit_canvas_to_parent: true,
                         prevent_default_event_handling: true,
@@ -71,18 +64,16 @@ impl Plugin for AppPlugin {
                     .into(),
                     ..default()
                 })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
-                })
                 .set(ImagePlugin::default_nearest()),
         );
 
         // Add other plugins.
-        app.add_plugins((
+        app.insert_resource(cli).add_plugins((
+            cli::plugin,
             word_loader::plugin,
+            settings::plugin,
+            save::plugin,
+            rng::plugin,
             game::plugin,
             screens::plugin,
             theme::plugin,
@@ -99,6 +90,29 @@ impl Plugin for AppPlugin {
     }
 }
 
+fn configure_app_sets(app: &mut App) {
+    app.configure_sets(
+        FixedUpdate,
+        (
+            AppSet::TickTimers,
+            AppSet::CollectInput,
+            AppSet::Update,
+            AppSet::UpdateCoords,
+        )
+            .chain(),
+    )
+    .configure_sets(
+        Update,
+        (
+            AppSet::TickTimers,
+            AppSet::CollectInput,
+            AppSet::Update,
+            AppSet::UpdateCoords,
+        )
+            .chain(),
+    );
+}
+
 /// High-level groupings of systems for the app in the `Update` schedule.
 /// When adding a new variant, make sure to order it in the `configure_sets`
 /// call above.
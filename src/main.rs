@@ -2,8 +2,19 @@
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
 use bevy::prelude::*;
-use gmtk_2024::AppPlugin;
+use gmtk_2024::{AppPlugin, CliArgs, CliError, USAGE};
 
 fn main() -> AppExit {
-    App::new().add_plugins(AppPlugin).run()
+    let cli = match CliArgs::from_env() {
+        Ok(cli) => cli,
+        Err(CliError::Help) => {
+            println!("{USAGE}");
+            return AppExit::Success;
+        }
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return AppExit::from_code(2);
+        }
+    };
+    App::new().add_plugins(AppPlugin { cli }).run()
 }
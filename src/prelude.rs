@@ -4,30 +4,39 @@
 pub(crate) use crate::tween::*;
 pub(crate) use crate::{
     anim::FadeOutSpriteHiearchy,
-    assets::{assets_exist, FontAssets, MusicAssets, SfxAssets, SpriteAssets},
+    assets::{
+        assets_exist, FontAssets, LevelAssets, MusicAssets, SfxAssets, SpriteAssets, WordlistAssets,
+    },
     audio::{
-        music::{MusicCommands, MusicTrack},
+        bus::{AudioBus, AudioBuses, MusicDucking},
+        music::{MusicCommands, MusicIntensity, MusicTrack},
         sfx::{Sfx, SfxCommands},
     },
     camera::HIGH_RES_RENDER_LAYER,
     ext::*,
     game::{
         level::{
-            level_ready, Ground, LevelEntityLookup, Movable, Moving, UnbreakableGround, TILE_SIZE,
+            level_ready, Ground, LevelEntityLookup, Movable, Moving, TileHardness,
+            UnbreakableGround, TILE_SIZE,
         },
         physics::{
             GamePhysicsLayer, Gravity, Grounded, HorizontalObstacleDetection, KinematicSensor,
             TileCollider, Velocity,
         },
         player::{
-            input::{PlayerBindings, TimedButtonInput, UiAction},
+            input::{Binding, KeyboardLayout, PlayerBindings, TimedButtonInput, UiAction},
             movement::{MovementEasing, MovementIntent},
-            Player,
+            DamagePlayer, Health, Player,
         },
         rock::Rock,
-        word::{WordTile, WordTileEvent, WordTileEventKind, WordTileStatus},
+        word::{
+            binding_blacklist, current_word_list, FloatingWord, MistypePolicy, ProgressDecay,
+            TileLocked, TypingTarget, WordListLanguage, WordTile, WordTileEvent, WordTileEventKind,
+            WordTileStatus, FLOATING_WORD_RANGE,
+        },
     },
     math::*,
+    rng::GameRng,
     screens::{in_game, transition::TransitionScreenCommandExt, Screen},
     theme::prelude::*,
     time::*,
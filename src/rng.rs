@@ -0,0 +1,142 @@
+//! Seeded random streams, so a run can be reproduced from its seed.
+//! Every level reseeds the streams from the [`RunSeed`] and the level index,
+//! which keeps e.g. the words of a level the same no matter what happened in the previous ones.
+
+use crate::{cli::CliArgs, game::goal::LevelIndex, prelude::*};
+use bevy::utils::SystemTime;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SeedSource>()
+        .register_type::<RunSeed>()
+        .init_resource::<SeedSource>()
+        .insert_resource(GameRng::new(0, 0))
+        // the source is applied from the settings in `PreStartup`
+        .add_systems(Startup, pick_run_seed)
+        .add_systems(
+            Update,
+            pick_run_seed
+                .run_if(resource_changed::<SeedSource>.and_then(not(resource_added::<SeedSource>))),
+        )
+        .add_systems(OnEnter(Screen::Game), reseed_level_streams);
+}
+
+/// Where the seed of the run comes from.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub(crate) enum SeedSource {
+    /// A new seed every launch.
+    #[default]
+    Random,
+    /// The same seed for everybody on the same day.
+    Daily,
+    Fixed(u64),
+}
+
+impl SeedSource {
+    /// Parse `random`, `daily` or a number.
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "random" => Some(Self::Random),
+            "daily" => Some(Self::Daily),
+            seed => seed.parse().ok().map(Self::Fixed),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Random => "Random".to_string(),
+            Self::Daily => "Daily".to_string(),
+            Self::Fixed(seed) => seed.to_string(),
+        }
+    }
+
+    fn seed(&self) -> u64 {
+        match self {
+            Self::Random => thread_rng().gen(),
+            Self::Daily => {
+                let days = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|since| since.as_secs() / (24 * 60 * 60))
+                    .unwrap_or_default();
+                mix(days)
+            }
+            Self::Fixed(seed) => *seed,
+        }
+    }
+}
+
+/// Seed of the current run, shown on the score screen so the run can be shared.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub(crate) struct RunSeed(pub u64);
+
+/// Independent streams so e.g. playing more sounds doesn't change the words.
+#[derive(Resource)]
+pub(crate) struct GameRng {
+    pub words: StdRng,
+    pub sfx: StdRng,
+    pub effects: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64, level: u64) -> Self {
+        let level_seed = mix(seed ^ mix(level));
+        Self {
+            words: StdRng::seed_from_u64(mix(level_seed ^ 1)),
+            sfx: StdRng::seed_from_u64(mix(level_seed ^ 2)),
+            effects: StdRng::seed_from_u64(mix(level_seed ^ 3)),
+        }
+    }
+}
+
+/// SplitMix64 finalizer, spreads similar inputs such as consecutive days over the whole range.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn pick_run_seed(source: Res<SeedSource>, cli: Res<CliArgs>, mut cmd: Commands) {
+    // `--seed` takes precedence over the settings
+    let source = cli.seed.unwrap_or(*source);
+    let seed = source.seed();
+    info!("Run seed: {seed} ({})", source.name());
+    cmd.insert_resource(RunSeed(seed));
+}
+
+fn reseed_level_streams(seed: Res<RunSeed>, lvl_index: Res<LevelIndex>, mut rng: ResMut<GameRng>) {
+    *rng = GameRng::new(seed.0, lvl_index.0 as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_and_level_give_the_same_streams() {
+        let mut a = GameRng::new(42, 1);
+        let mut b = GameRng::new(42, 1);
+        assert_eq!(a.words.gen::<u64>(), b.words.gen::<u64>());
+        // the other streams don't affect the words
+        a.sfx.gen::<u64>();
+        assert_eq!(a.words.gen::<u64>(), b.words.gen::<u64>());
+    }
+
+    #[test]
+    fn levels_and_streams_differ() {
+        let mut level_0 = GameRng::new(42, 0);
+        let mut level_1 = GameRng::new(42, 1);
+        assert_ne!(level_0.words.gen::<u64>(), level_1.words.gen::<u64>());
+        assert_ne!(level_0.words.gen::<u64>(), level_0.sfx.gen::<u64>());
+    }
+
+    #[test]
+    fn parses_seed_sources() {
+        assert_eq!(SeedSource::parse("daily"), Some(SeedSource::Daily));
+        assert_eq!(SeedSource::parse("random"), Some(SeedSource::Random));
+        assert_eq!(SeedSource::parse("123"), Some(SeedSource::Fixed(123)));
+        assert_eq!(SeedSource::parse("abc"), None);
+    }
+}
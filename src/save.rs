@@ -0,0 +1,223 @@
+//! Campaign progress and the best results persisted between sessions.
+//! Stored next to the [`Settings`](crate::settings::Settings) and autosaved whenever a level is completed.
+
+use crate::{
+    game::{
+        campaign::CampaignProgress, goal::LevelCompleted, level::CollectedOre,
+        replay::ReplayPlayback, stats::LevelStats,
+    },
+    prelude::*,
+    storage::{self, StorageError},
+};
+use bevy::asset::ron;
+use serde::{Deserialize, Serialize};
+
+const STORAGE_NAME: &str = "save";
+/// Bump when the format changes and convert the older saves in [`SaveGame::parse`].
+const SAVE_VERSION: u32 = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SaveGame>()
+        .init_resource::<SaveGame>()
+        .add_systems(PreStartup, (load_save, apply_save).chain())
+        .add_systems(
+            Update,
+            // watching a replay doesn't count as playing the level
+            autosave_completed_level.run_if(
+                on_event::<LevelCompleted>().and_then(not(resource_exists::<ReplayPlayback>)),
+            ),
+        );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub(crate) struct SaveGame {
+    pub version: u32,
+    pub progress: CampaignProgress,
+    /// Best results of the completed levels.
+    pub records: Vec<LevelRecord>,
+}
+
+impl Default for SaveGame {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            progress: default(),
+            records: default(),
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct LevelRecord {
+    pub iid: String,
+    /// Fastest completion, in seconds.
+    pub best_time: f32,
+    pub best_wpm: f32,
+    /// Most ore collected in a single completion.
+    pub best_ore: u32,
+}
+
+/// Just the version, read before the rest of the save.
+#[derive(Deserialize)]
+struct SaveVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SaveError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("could not parse the save: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not serialize the save: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("unsupported save version {0}")]
+    Version(u32),
+}
+
+impl SaveGame {
+    /// Read the stored save, starting a new one when there's none or it's invalid.
+    fn load() -> Self {
+        match Self::read() {
+            Ok(save) => save.unwrap_or_default(),
+            Err(err) => {
+                warn!("Starting without a save: {err}");
+                SaveGame::default()
+            }
+        }
+    }
+
+    fn read() -> Result<Option<Self>, SaveError> {
+        let Some(stored) = storage::read(STORAGE_NAME)? else {
+            return Ok(None);
+        };
+        Self::parse(&stored).map(Some)
+    }
+
+    /// Parse the save, migrating it from the older versions.
+    fn parse(stored: &str) -> Result<Self, SaveError> {
+        let SaveVersion { version } = ron::from_str(stored)?;
+        match version {
+            SAVE_VERSION => Ok(ron::from_str(stored)?),
+            // the conversions of the older versions go here, e.g.
+            // `0 => Ok(ron::from_str::<SaveV0>(stored)?.into())`
+            _ => Err(SaveError::Version(version)),
+        }
+    }
+
+    fn save(&self) -> Result<(), SaveError> {
+        let serialized = ron::ser::to_string_pretty(self, default())?;
+        Ok(storage::write(STORAGE_NAME, &serialized)?)
+    }
+
+    pub fn record(&self, iid: &str) -> Option<&LevelRecord> {
+        self.records.iter().find(|record| record.iid == iid)
+    }
+
+    /// The completion is faster than the stored record, or the first one.
+    pub fn is_best_time(&self, iid: &str, time: f32) -> bool {
+        self.record(iid)
+            .map_or(true, |record| time < record.best_time)
+    }
+
+    /// Keep the better of the stored and the new results.
+    fn update_record(&mut self, iid: &str, stats: &LevelStats, ore: u32) {
+        let new = LevelRecord {
+            iid: iid.to_string(),
+            best_time: stats.time,
+            best_wpm: stats.wpm(),
+            best_ore: ore,
+        };
+        match self.records.iter_mut().find(|record| record.iid == iid) {
+            Some(record) => {
+                record.best_time = record.best_time.min(new.best_time);
+                record.best_wpm = record.best_wpm.max(new.best_wpm);
+                record.best_ore = record.best_ore.max(new.best_ore);
+            }
+            None => self.records.push(new),
+        }
+    }
+}
+
+fn load_save(mut save: ResMut<SaveGame>) {
+    *save = SaveGame::load();
+}
+
+fn apply_save(save: Res<SaveGame>, mut progress: ResMut<CampaignProgress>) {
+    *progress = save.progress.clone();
+}
+
+pub(crate) fn autosave_completed_level(
+    mut completed_evr: EventReader<LevelCompleted>,
+    progress: Res<CampaignProgress>,
+    stats: Res<LevelStats>,
+    ore: Res<CollectedOre>,
+    mut save: ResMut<SaveGame>,
+) {
+    for ev in completed_evr.read() {
+        save.update_record(&ev.iid, &stats, ore.0);
+    }
+    save.progress = progress.clone();
+    if let Err(err) = save.save() {
+        warn!("Failed to save the game: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_round_trips() {
+        let mut save = SaveGame::default();
+        save.progress.complete("a");
+        save.update_record(
+            "a",
+            &LevelStats {
+                chars_typed: 50,
+                time: 60.,
+                ..default()
+            },
+            3,
+        );
+        let serialized = ron::ser::to_string_pretty(&save, default()).unwrap();
+        assert_eq!(SaveGame::parse(&serialized).unwrap(), save);
+    }
+
+    #[test]
+    fn newer_save_is_rejected() {
+        let stored = format!("(version: {}, records: [])", SAVE_VERSION + 1);
+        assert!(matches!(
+            SaveGame::parse(&stored),
+            Err(SaveError::Version(version)) if version == SAVE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn records_keep_the_best_results() {
+        let mut save = SaveGame::default();
+        let slow = LevelStats {
+            chars_typed: 100,
+            time: 120.,
+            ..default()
+        };
+        let fast = LevelStats {
+            chars_typed: 100,
+            time: 60.,
+            ..default()
+        };
+        save.update_record("a", &fast, 1);
+        save.update_record("a", &slow, 4);
+        let record = save.record("a").unwrap();
+        assert_eq!(record.best_time, 60.);
+        assert!(!save.is_best_time("a", 60.));
+        assert!(save.is_best_time("a", 59.));
+        assert!(save.is_best_time("b", 120.));
+        assert_eq!(record.best_wpm, fast.wpm());
+        assert_eq!(record.best_ore, 4);
+        assert_eq!(save.records.len(), 1);
+    }
+}
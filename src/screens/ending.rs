@@ -0,0 +1,26 @@
+//! Shown once the last level of the campaign is completed.
+
+use crate::prelude::*;
+
+use super::{trigger_transition_to_credits, trigger_transition_to_main_menu};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Ending), show_ending_screen);
+}
+
+fn show_ending_screen(mut cmd: Commands) {
+    cmd.ui_root()
+        .insert(StateScoped(Screen::Ending))
+        .with_children(|children| {
+            children.header("The End");
+            children.label("Every level has been dug through.");
+            children.label("Thanks for playing!");
+
+            children
+                .button("Credits")
+                .observe(trigger_transition_to_credits);
+            children
+                .button("Menu")
+                .observe(trigger_transition_to_main_menu);
+        });
+}
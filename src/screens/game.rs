@@ -4,7 +4,7 @@ use crate::prelude::*;
 use leafwing_input_manager::common_conditions::action_just_pressed;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnExit(Screen::Game), stop_music)
+    app.add_systems(OnEnter(Screen::Game), play_game_music)
         .add_systems(OnEnter(Screen::RestartGame), enter_restart)
         .add_systems(
             Update,
@@ -17,8 +17,9 @@ pub(super) fn plugin(app: &mut App) {
         );
 }
 
-fn stop_music(mut commands: Commands) {
-    commands.stop_music();
+/// Keeps playing when the game is restarted.
+fn play_game_music(mut cmd: Commands) {
+    cmd.play_music(MusicTrack::Game);
 }
 
 fn return_to_main_menu(mut cmd: Commands) {
@@ -0,0 +1,94 @@
+//! Pick any of the unlocked levels of the campaign.
+
+use crate::{
+    game::{
+        campaign::{campaign_levels, CampaignProgress, LevelState},
+        goal::LevelIndex,
+        stats::format_time,
+    },
+    prelude::*,
+    save::SaveGame,
+};
+use bevy::ui::Val::*;
+
+use super::trigger_transition_to_main_menu;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::LevelSelect), show_screen);
+}
+
+#[derive(Component)]
+struct LevelButton(usize);
+
+fn show_screen(
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    progress: Res<CampaignProgress>,
+    save: Res<SaveGame>,
+    mut cmd: Commands,
+) {
+    let levels = campaign_levels(&levels, &projects);
+    cmd.ui_root()
+        .insert(StateScoped(Screen::LevelSelect))
+        .with_children(|children| {
+            children.header("Levels");
+            for (i, level) in levels.iter().enumerate() {
+                let state = progress.level_state(&levels, i);
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Px(10.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        let mut button = row.button(level.name.clone());
+                        button.insert(LevelButton(i)).observe(play_level);
+                        if state == LevelState::Locked {
+                            let dimmed = ui_palette::NODE_BACKGROUND.with_alpha(0.3);
+                            button.insert((
+                                BackgroundColor(dimmed),
+                                InteractionPalette {
+                                    none: dimmed,
+                                    hovered: dimmed,
+                                    pressed: dimmed,
+                                },
+                            ));
+                        }
+                        row.label(match (state, save.record(&level.iid)) {
+                            (LevelState::Completed, Some(record)) => format!(
+                                "Best {} - {:.0} WPM - {} ore",
+                                format_time(record.best_time),
+                                record.best_wpm,
+                                record.best_ore
+                            ),
+                            (LevelState::Completed, None) => "Completed".to_string(),
+                            (LevelState::Unlocked, _) => "Unlocked".to_string(),
+                            (LevelState::Locked, _) => "Locked".to_string(),
+                        });
+                    });
+            }
+
+            children
+                .button("Back")
+                .observe(trigger_transition_to_main_menu);
+        });
+}
+
+fn play_level(
+    trigger: Trigger<OnPress>,
+    button_q: Query<&LevelButton>,
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    progress: Res<CampaignProgress>,
+    mut lvl_index: ResMut<LevelIndex>,
+    mut cmd: Commands,
+) {
+    let index = or_return!(button_q.get(trigger.entity())).0;
+    let levels = campaign_levels(&levels, &projects);
+    or_return_quiet!(progress.level_state(&levels, index) != LevelState::Locked);
+    lvl_index.0 = index;
+    cmd.transition_to_screen(Screen::Game);
+}
@@ -1,19 +1,39 @@
-use crate::prelude::*;
+use crate::{
+    game::{
+        campaign::{campaign_levels, CampaignProgress},
+        goal::LevelIndex,
+    },
+    prelude::*,
+};
 
 use super::{
-    trigger_transition_to_credits, trigger_transition_to_game, trigger_transition_to_settings,
-    trigger_transition_to_tutorial,
+    trigger_transition_to_credits, trigger_transition_to_level_select,
+    trigger_transition_to_settings, trigger_transition_to_tutorial,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::MainMenu), show_title_screen);
 }
 
-fn show_title_screen(mut cmd: Commands) {
+fn show_title_screen(
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    progress: Res<CampaignProgress>,
+    mut cmd: Commands,
+) {
+    let can_continue = progress.has_started()
+        && progress
+            .next_level(&campaign_levels(&levels, &projects))
+            .is_some();
     cmd.ui_root()
         .insert(StateScoped(Screen::MainMenu))
         .with_children(|children| {
-            children.button("Play").observe(trigger_transition_to_game);
+            if can_continue {
+                children.button("Continue").observe(continue_campaign);
+            }
+            children
+                .button("Play")
+                .observe(trigger_transition_to_level_select);
             children
                 .button("Tutorial")
                 .observe(trigger_transition_to_tutorial);
@@ -31,6 +51,19 @@ fn show_title_screen(mut cmd: Commands) {
     cmd.play_music(MusicTrack::MainMenu);
 }
 
+/// Play the first level that hasn't been completed yet.
+fn continue_campaign(
+    _trigger: Trigger<OnPress>,
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    progress: Res<CampaignProgress>,
+    mut lvl_index: ResMut<LevelIndex>,
+    mut cmd: Commands,
+) {
+    lvl_index.0 = or_return!(progress.next_level(&campaign_levels(&levels, &projects)));
+    cmd.transition_to_screen(Screen::Game);
+}
+
 #[cfg(not(target_family = "wasm"))]
 fn exit_app(_trigger: Trigger<OnPress>, mut app_exit: EventWriter<AppExit>) {
     app_exit.send(AppExit::Success);
@@ -1,9 +1,12 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod ending;
 mod game;
+mod level_select;
 mod loading;
 mod main_menu;
+mod score;
 mod settings;
 mod splash;
 pub(crate) mod transition;
@@ -21,6 +24,9 @@ pub(super) fn plugin(app: &mut App) {
         main_menu::plugin,
         credits::plugin,
         game::plugin,
+        score::plugin,
+        level_select::plugin,
+        ending::plugin,
         settings::plugin,
         tutorial::plugin,
         transition::plugin,
@@ -35,12 +41,14 @@ pub enum Screen {
     #[cfg_attr(feature = "dev", default)]
     Loading,
     MainMenu,
+    LevelSelect,
     Settings,
     Tutorial,
     Credits,
     Game,
     RestartGame,
     Score,
+    Ending,
     Quit,
 }
 
@@ -56,6 +64,7 @@ macro_rules! transition_system {
 
 transition_system!(main_menu, MainMenu);
 transition_system!(game, Game);
+transition_system!(level_select, LevelSelect);
 transition_system!(tutorial, Tutorial);
 transition_system!(settings, Settings);
 transition_system!(credits, Credits);
@@ -0,0 +1,72 @@
+//! Stats of the finished level shown between the levels.
+
+use crate::{
+    game::{
+        campaign::campaign_levels,
+        goal::LevelIndex,
+        level::CollectedOre,
+        replay::{start_playback, Replay},
+        stats::{format_time, LevelStats},
+    },
+    prelude::*,
+    rng::RunSeed,
+};
+
+use super::{trigger_transition_to_game, trigger_transition_to_main_menu};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Score), show_score_screen);
+}
+
+fn show_score_screen(
+    stats: Res<LevelStats>,
+    ore: Res<CollectedOre>,
+    seed: Res<RunSeed>,
+    lvl_index: Res<LevelIndex>,
+    levels: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+    replay: Option<Res<Replay>>,
+    mut cmd: Commands,
+) {
+    let last_level = lvl_index.0 + 1 >= campaign_levels(&levels, &projects).len();
+    cmd.ui_root()
+        .insert(StateScoped(Screen::Score))
+        .with_children(|children| {
+            children.header("Level complete");
+            children.label(format!("WPM: {:.0}", stats.wpm()));
+            children.label(format!("Accuracy: {:.0}%", stats.accuracy() * 100.));
+            children.label(format!("Characters typed: {}", stats.chars_typed));
+            children.label(format!("Tiles mined: {}", stats.tiles_mined));
+            children.label(format!("Ore: {}", ore.0));
+            children.label(format!("Time: {}", format_time(stats.time)));
+            children.label(format!("Jumps: {}", stats.jumps));
+            children.label(format!("Deaths: {}", stats.deaths));
+            children.label(format!("Seed: {}", seed.0));
+
+            if last_level {
+                children.button("Finish").observe(finish_campaign);
+            } else {
+                children.button("Next").observe(next_level);
+            }
+            children.button("Retry").observe(trigger_transition_to_game);
+            if replay.is_some() {
+                children.button("Watch replay").observe(watch_replay);
+            }
+            children
+                .button("Menu")
+                .observe(trigger_transition_to_main_menu);
+        });
+}
+
+fn next_level(_trigger: Trigger<OnPress>, mut lvl_index: ResMut<LevelIndex>, mut cmd: Commands) {
+    lvl_index.0 += 1;
+    cmd.transition_to_screen(Screen::Game);
+}
+
+fn watch_replay(_trigger: Trigger<OnPress>, replay: Res<Replay>, mut cmd: Commands) {
+    start_playback(&mut cmd, replay.clone());
+}
+
+fn finish_campaign(_trigger: Trigger<OnPress>, mut cmd: Commands) {
+    cmd.transition_to_screen(Screen::Ending);
+}
@@ -1,23 +1,276 @@
-//! A credits screen that can be accessed from the title screen.
+//! A settings screen that can be accessed from the title screen.
 
-use crate::prelude::*;
+use crate::{prelude::*, rng::SeedSource};
+use bevy::ui::Val::*;
 
 use super::trigger_transition_to_main_menu;
 
+const VOLUME_STEP: f32 = 0.1;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Settings), show_screen);
+    app.init_resource::<Rebind>()
+        .add_systems(OnEnter(Screen::Settings), show_screen)
+        .add_systems(
+            Update,
+            (
+                capture_rebind_key,
+                update_binding_texts.run_if(
+                    resource_changed::<Rebind>
+                        .or_else(resource_changed::<PlayerBindings>)
+                        .or_else(resource_changed::<KeyboardLayout>)
+                        .or_else(resource_changed::<WordListLanguage>),
+                ),
+            )
+                .chain()
+                .run_if(in_state(Screen::Settings)),
+        );
+}
+
+/// State of the binding that is being changed.
+#[derive(Resource, Default)]
+struct Rebind {
+    /// Waiting for a key press.
+    capturing: Option<Binding>,
+    /// The last pressed key is already used by another binding.
+    conflict: Option<(KeyCode, Binding)>,
 }
 
-fn show_screen(mut commands: Commands) {
-    commands
-        .ui_root()
+#[derive(Component)]
+struct BindingButton(Binding);
+
+#[derive(Component)]
+struct BindingStatus;
+
+#[derive(Component)]
+struct VolumeButton(AudioBus);
+
+fn show_screen(
+    language: Res<WordListLanguage>,
+    buses: Res<AudioBuses>,
+    seed: Res<SeedSource>,
+    mut rebind: ResMut<Rebind>,
+    mut cmd: Commands,
+) {
+    *rebind = Rebind::default();
+    cmd.ui_root()
         // todo: replace by ScreenTransition scoped instead?
         .insert(StateScoped(Screen::Settings))
         .with_children(|children| {
             children.header("Settings");
-            children.label("TODO");
+            children.label("Word list language");
+            children
+                .button(language.to_uppercase())
+                .observe(cycle_language);
+
+            children.label("Seed");
+            children.button(seed.name()).observe(cycle_seed_source);
+
+            children.label("Volume");
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for bus in AudioBus::ALL {
+                        row.button(volume_text(bus, &buses))
+                            .insert(VolumeButton(bus))
+                            .observe(cycle_volume);
+                    }
+                });
+
+            children.header("Controls");
+            for binding in Binding::ALL {
+                children
+                    .button(binding.name())
+                    .insert(BindingButton(binding))
+                    .observe(start_rebind);
+            }
+            children.label("").insert(BindingStatus);
+            children.button("Reset").observe(reset_bindings);
+
             children
                 .button("Back")
                 .observe(trigger_transition_to_main_menu);
         });
 }
+
+fn cycle_language(
+    trigger: Trigger<OnPress>,
+    wordlist_assets: Res<WordlistAssets>,
+    mut language: ResMut<WordListLanguage>,
+    children_q: Query<&Children>,
+    mut text_q: Query<&mut Text>,
+) {
+    let codes = wordlist_assets.language_codes();
+    let next_i = codes
+        .iter()
+        .position(|code| *code == language.0)
+        .map_or(0, |i| (i + 1) % codes.len());
+    language.0 = or_return!(codes.get(next_i)).clone();
+    set_text(
+        trigger.entity(),
+        language.to_uppercase(),
+        &children_q,
+        &mut text_q,
+    );
+}
+
+/// Switch between a random and a daily seed, a fixed seed can only be set in the settings file.
+fn cycle_seed_source(
+    trigger: Trigger<OnPress>,
+    mut seed: ResMut<SeedSource>,
+    children_q: Query<&Children>,
+    mut text_q: Query<&mut Text>,
+) {
+    *seed = match *seed {
+        SeedSource::Random => SeedSource::Daily,
+        SeedSource::Daily | SeedSource::Fixed(_) => SeedSource::Random,
+    };
+    set_text(trigger.entity(), seed.name(), &children_q, &mut text_q);
+}
+
+fn volume_text(bus: AudioBus, buses: &AudioBuses) -> String {
+    format!(
+        "{} {}%",
+        bus.name(),
+        (buses.volume(bus) * 100.).round() as u32
+    )
+}
+
+/// Raise the volume by a step, wrapping around to silence after the max.
+fn cycle_volume(
+    trigger: Trigger<OnPress>,
+    button_q: Query<&VolumeButton>,
+    mut buses: ResMut<AudioBuses>,
+    children_q: Query<&Children>,
+    mut text_q: Query<&mut Text>,
+) {
+    let bus = or_return!(button_q.get(trigger.entity())).0;
+    let volume = buses.volume(bus);
+    let next = if volume >= 1. - VOLUME_STEP / 2. {
+        0.
+    } else {
+        ((volume + VOLUME_STEP) / VOLUME_STEP).round() * VOLUME_STEP
+    };
+    buses.set_volume(bus, next);
+    set_text(
+        trigger.entity(),
+        volume_text(bus, &buses),
+        &children_q,
+        &mut text_q,
+    );
+}
+
+fn start_rebind(
+    trigger: Trigger<OnPress>,
+    button_q: Query<&BindingButton>,
+    mut rebind: ResMut<Rebind>,
+) {
+    let button = or_return!(button_q.get(trigger.entity()));
+    *rebind = Rebind {
+        capturing: Some(button.0),
+        conflict: None,
+    };
+}
+
+fn reset_bindings(
+    _trigger: Trigger<OnPress>,
+    mut bindings: ResMut<PlayerBindings>,
+    mut rebind: ResMut<Rebind>,
+) {
+    *bindings = PlayerBindings::default();
+    *rebind = Rebind::default();
+}
+
+fn capture_rebind_key(
+    input: Res<ButtonInput<KeyCode>>,
+    mut rebind: ResMut<Rebind>,
+    mut bindings: ResMut<PlayerBindings>,
+) {
+    let binding = or_return_quiet!(rebind.capturing);
+    // skip the frame the capture has started in
+    or_return_quiet!(!rebind.is_changed());
+    let key = *or_return_quiet!(input.get_just_pressed().next());
+    if key == KeyCode::Escape {
+        *rebind = Rebind::default();
+        return;
+    }
+
+    if let Some(other) = bindings.conflict(binding, key) {
+        rebind.conflict = Some((key, other));
+        return;
+    }
+    if bindings.get(binding) != key {
+        bindings.set(binding, key);
+    }
+    *rebind = Rebind::default();
+}
+
+fn update_binding_texts(
+    rebind: Res<Rebind>,
+    bindings: Res<PlayerBindings>,
+    layout: Res<KeyboardLayout>,
+    wordlists: Res<Assets<WordListSource>>,
+    wordlist_assets: Res<WordlistAssets>,
+    language: Res<WordListLanguage>,
+    button_q: Query<(Entity, &BindingButton)>,
+    status_q: Query<Entity, With<BindingStatus>>,
+    children_q: Query<&Children>,
+    mut text_q: Query<&mut Text>,
+) {
+    for (e, button) in &button_q {
+        let key_name = if rebind.capturing == Some(button.0) {
+            "?".to_string()
+        } else {
+            layout.key_name(bindings.get(button.0))
+        };
+        let text = format!("{}: {key_name}", button.0.name());
+        set_text(e, text, &children_q, &mut text_q);
+    }
+
+    let status = match (rebind.capturing, rebind.conflict) {
+        (Some(binding), Some((key, other))) => format!(
+            "{} is already used for {}, press another key for {}",
+            layout.key_name(key),
+            other.name(),
+            binding.name()
+        ),
+        (Some(binding), None) => format!("Press a key for {}, Esc to cancel", binding.name()),
+        _ => {
+            let blacklist = binding_blacklist(&bindings, &layout);
+            current_word_list(&wordlists, &wordlist_assets, &language)
+                .map(|source| {
+                    let unusable = source.untypeable_words(&blacklist).count();
+                    if unusable > 0 {
+                        format!(
+                            "{unusable} of {} words contain the bound keys and won't be used",
+                            source.words.len()
+                        )
+                    } else {
+                        String::new()
+                    }
+                })
+                .unwrap_or_default()
+        }
+    };
+    for e in &status_q {
+        set_text(e, status.clone(), &children_q, &mut text_q);
+    }
+}
+
+fn set_text(
+    e: Entity,
+    value: String,
+    children_q: &Query<&Children>,
+    text_q: &mut Query<&mut Text>,
+) {
+    for child in children_q.iter_descendants(e) {
+        if let Ok(mut text) = text_q.get_mut(child) {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
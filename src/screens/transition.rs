@@ -12,11 +12,8 @@ pub(super) fn plugin(app: &mut App) {
                 start_transition_in,
             ),
         )
-        .insert_resource(TransitionSpeedFactor(if cfg!(feature = "dev") {
-            0.5
-        } else {
-            1.0
-        }));
+        // overwritten by the stored settings
+        .init_resource::<TransitionSpeedFactor>();
 }
 
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
@@ -54,6 +51,12 @@ impl<'w, 's> TransitionScreenCommandExt for Commands<'w, 's> {
 #[derive(Resource, Deref, DerefMut)]
 pub struct TransitionSpeedFactor(pub f32);
 
+impl Default for TransitionSpeedFactor {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 impl TransitionSpeedFactor {
     pub fn duration(&self, base_duration: u64) -> u64 {
         (base_duration as f32 * self.0) as u64
@@ -0,0 +1,138 @@
+//! Player settings persisted between sessions.
+//! Stored as RON in the config dir on native and in the `localStorage` on the web.
+
+use crate::{
+    audio::bus::AudioBuses,
+    cli::CliArgs,
+    prelude::*,
+    rng::SeedSource,
+    screens::transition::TransitionSpeedFactor,
+    storage::{self, StorageError},
+};
+use bevy::asset::ron;
+use serde::{Deserialize, Serialize};
+
+const STORAGE_NAME: &str = "settings";
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Settings>()
+        .init_resource::<Settings>()
+        // applied before the loading screen is entered
+        .add_systems(PreStartup, (load_settings, apply_settings).chain())
+        .add_systems(
+            Update,
+            (
+                collect_settings,
+                save_settings
+                    .run_if(resource_changed::<Settings>.and_then(not(resource_added::<Settings>))),
+            )
+                .chain(),
+        );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub bindings: PlayerBindings,
+    pub audio: AudioBuses,
+    /// Multiplier of the screen transition durations.
+    pub transition_speed: f32,
+    pub language: String,
+    pub seed: SeedSource,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            bindings: default(),
+            audio: default(),
+            transition_speed: if cfg!(feature = "dev") { 0.5 } else { 1.0 },
+            language: WordListLanguage::default().0,
+            seed: default(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SettingsError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("could not parse the settings: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not serialize the settings: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+impl Settings {
+    /// Read the stored settings, falling back to the defaults when there are none or they are invalid.
+    fn load() -> Self {
+        match Self::read() {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(err) => {
+                warn!("Using the default settings: {err}");
+                Settings::default()
+            }
+        }
+    }
+
+    fn read() -> Result<Option<Self>, SettingsError> {
+        let Some(stored) = storage::read(STORAGE_NAME)? else {
+            return Ok(None);
+        };
+        Ok(Some(ron::from_str(&stored)?))
+    }
+
+    fn save(&self) -> Result<(), SettingsError> {
+        let serialized = ron::ser::to_string_pretty(self, default())?;
+        Ok(storage::write(STORAGE_NAME, &serialized)?)
+    }
+}
+
+fn load_settings(mut settings: ResMut<Settings>) {
+    *settings = Settings::load();
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut bindings: ResMut<PlayerBindings>,
+    mut buses: ResMut<AudioBuses>,
+    mut transition_speed: ResMut<TransitionSpeedFactor>,
+    mut language: ResMut<WordListLanguage>,
+    mut seed: ResMut<SeedSource>,
+) {
+    *bindings = settings.bindings.clone();
+    *buses = settings.audio.clone();
+    transition_speed.0 = settings.transition_speed;
+    language.0 = settings.language.clone();
+    *seed = settings.seed;
+}
+
+fn collect_settings(
+    cli: Res<CliArgs>,
+    bindings: Res<PlayerBindings>,
+    buses: Res<AudioBuses>,
+    transition_speed: Res<TransitionSpeedFactor>,
+    language: Res<WordListLanguage>,
+    seed: Res<SeedSource>,
+    mut settings: ResMut<Settings>,
+) {
+    let bindings = match &cli.bindings {
+        // the bindings of the command line aren't saved, the ones changed in the settings are
+        Some(cli_bindings) if *cli_bindings == *bindings => settings.bindings.clone(),
+        _ => bindings.clone(),
+    };
+    settings.set_if_neq(Settings {
+        bindings,
+        audio: buses.clone(),
+        transition_speed: transition_speed.0,
+        language: language.0.clone(),
+        seed: *seed,
+    });
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if let Err(err) = settings.save() {
+        warn!("Failed to save the settings: {err}");
+    }
+}
@@ -0,0 +1,70 @@
+//! Small named documents stored between sessions.
+//! Files in the config dir on native and the `localStorage` on the web.
+
+#[derive(Debug, thiserror::Error)]
+#[error("could not access the storage: {0}")]
+pub(crate) struct StorageError(String);
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::StorageError;
+    use std::{fs, io::ErrorKind, path::PathBuf};
+
+    fn path(name: &str) -> Result<PathBuf, StorageError> {
+        directories::ProjectDirs::from("", "", crate::GAME_NAME)
+            .map(|dirs| dirs.config_dir().join(format!("{name}.ron")))
+            .ok_or_else(|| StorageError("no config dir".to_string()))
+    }
+
+    pub(crate) fn read(name: &str) -> Result<Option<String>, StorageError> {
+        match fs::read_to_string(path(name)?) {
+            Ok(stored) => Ok(Some(stored)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(StorageError(err.to_string())),
+        }
+    }
+
+    /// Writes a temporary file first so a crash can't leave a half written document behind.
+    pub(crate) fn write(name: &str, serialized: &str) -> Result<(), StorageError> {
+        let path = path(name)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| StorageError(err.to_string()))?;
+        }
+        let tmp_path = path.with_extension("ron.tmp");
+        fs::write(&tmp_path, serialized).map_err(|err| StorageError(err.to_string()))?;
+        fs::rename(tmp_path, path).map_err(|err| StorageError(err.to_string()))
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod web {
+    use super::StorageError;
+
+    /// Prefixed since the origin can be shared with other games, e.g. on itch.
+    fn key(name: &str) -> String {
+        format!("{}.{name}", crate::GAME_NAME)
+    }
+
+    fn local_storage() -> Result<web_sys::Storage, StorageError> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| StorageError("no localStorage".to_string()))
+    }
+
+    pub(crate) fn read(name: &str) -> Result<Option<String>, StorageError> {
+        local_storage()?
+            .get_item(&key(name))
+            .map_err(|err| StorageError(format!("{err:?}")))
+    }
+
+    pub(crate) fn write(name: &str, serialized: &str) -> Result<(), StorageError> {
+        local_storage()?
+            .set_item(&key(name), serialized)
+            .map_err(|err| StorageError(format!("{err:?}")))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub(crate) use native::{read, write};
+#[cfg(target_family = "wasm")]
+pub(crate) use web::{read, write};
@@ -0,0 +1,211 @@
+//! Headless app with the gameplay plugins for the integration tests.
+//! Runs on [`MinimalPlugins`] without a window or a GPU, plays the level of `tests/fixtures/harness.ldtk`
+//! and advances the time by exactly one fixed tick per [`TestApp::step`].
+
+use crate::{
+    assets::loading_state, cli::CliArgs, game::player::input::PlayerAction, prelude::*,
+    rng::SeedSource,
+};
+use bevy::{
+    asset::{io::AssetSource, AssetMetaCheck},
+    audio::AudioPlugin,
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey, NativeKeyCode},
+        InputPlugin,
+    },
+    render::render_resource::Shader,
+    state::app::StatesPlugin,
+    text::TextPlugin,
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+};
+use bevy_asset_loader::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use std::time::Duration;
+
+const FIXTURE: &str = "fixtures://harness.ldtk";
+/// Updates to wait for the assets and the level before giving up.
+const MAX_LOADING_UPDATES: usize = 5000;
+
+pub(crate) struct TestApp {
+    app: App,
+}
+
+impl TestApp {
+    /// App with the fixture level spawned and ready to be played.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        // has to be registered before the `AssetPlugin`
+        app.register_asset_source(
+            "fixtures",
+            AssetSource::build().with_reader(AssetSource::get_default_reader(
+                "tests/fixtures".to_string(),
+            )),
+        );
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            },
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            },
+            StatesPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            InputPlugin,
+            ImagePlugin::default_nearest(),
+            AudioPlugin::default(),
+        ))
+        // registered by the render and sprite plugins otherwise
+        .init_asset::<Shader>()
+        .init_asset::<TextureAtlasLayout>()
+        .add_plugins(TextPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ));
+        crate::configure_app_sets(&mut app);
+
+        app.insert_state(Screen::Loading)
+            .enable_state_scoped_entities::<Screen>()
+            // the fixture level replaces the campaign once the assets are loaded
+            .add_loading_state(loading_state().continue_to_state(Screen::MainMenu))
+            .add_plugins((
+                crate::word_loader::plugin,
+                crate::rng::plugin,
+                crate::audio::plugin,
+                crate::game::gameplay_plugin,
+            ))
+            .insert_resource(SeedSource::Fixed(0))
+            .init_resource::<CliArgs>();
+
+        let mut test_app = Self { app };
+        test_app.update_until("the assets", |world| {
+            *world.resource::<State<Screen>>().get() == Screen::MainMenu
+        });
+
+        let project = test_app
+            .world()
+            .resource::<AssetServer>()
+            .load::<LdtkProject>(FIXTURE);
+        test_app.update_until("the fixture", |world| {
+            world
+                .resource::<AssetServer>()
+                .is_loaded_with_dependencies(&project)
+        });
+        test_app.app.insert_resource(LevelAssets { project });
+        test_app
+            .world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Game);
+        test_app.update_until("the level", |world| {
+            world.contains_resource::<LevelEntityLookup>()
+                && world
+                    .query_filtered::<(), (With<Player>, With<KinematicSensor>)>()
+                    .iter(world)
+                    .next()
+                    .is_some()
+                && world.query::<&WordTile>().iter(world).next().is_some()
+        });
+        test_app
+    }
+
+    fn update_until(&mut self, what: &str, done: impl Fn(&mut World) -> bool) {
+        for _ in 0..MAX_LOADING_UPDATES {
+            if done(self.app.world_mut()) {
+                return;
+            }
+            self.app.update();
+            // let the asset loading tasks progress
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("Timed out waiting for {what}");
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Run the given number of fixed ticks.
+    pub fn step(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    pub fn player(&mut self) -> Entity {
+        let world = self.app.world_mut();
+        world.query_filtered::<Entity, With<Player>>().single(world)
+    }
+
+    pub fn action_state(&self) -> &ActionState<PlayerAction> {
+        self.world().resource::<ActionState<PlayerAction>>()
+    }
+
+    /// Press the bound key, the [`ActionState`] is updated on the next step.
+    pub fn hold(&mut self, binding: Binding) {
+        let key = self.world().resource::<PlayerBindings>().get(binding);
+        self.send_key(key, unidentified_key(), bevy::input::ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, binding: Binding) {
+        let key = self.world().resource::<PlayerBindings>().get(binding);
+        self.send_key(key, unidentified_key(), bevy::input::ButtonState::Released);
+    }
+
+    /// Type the text, it's read on the next step.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            for state in [
+                bevy::input::ButtonState::Pressed,
+                bevy::input::ButtonState::Released,
+            ] {
+                self.send_key(
+                    KeyCode::Unidentified(NativeKeyCode::Unidentified),
+                    Key::Character(c.to_string().into()),
+                    state,
+                );
+            }
+        }
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, logical_key: Key, state: bevy::input::ButtonState) {
+        self.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
+
+/// Binding keys aren't typed, so their logical key doesn't matter.
+fn unidentified_key() -> Key {
+    Key::Unidentified(NativeKey::Unidentified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_bindings_drive_the_action_state() {
+        let mut app = TestApp::new();
+        app.hold(Binding::Jump);
+        app.step(1);
+        assert!(app.action_state().just_pressed(&PlayerAction::Jump));
+        app.step(1);
+        assert!(app.action_state().pressed(&PlayerAction::Jump));
+        assert!(!app.action_state().just_pressed(&PlayerAction::Jump));
+        app.release(Binding::Jump);
+        app.step(1);
+        assert!(app.action_state().just_released(&PlayerAction::Jump));
+    }
+}
@@ -2,42 +2,235 @@ use crate::prelude::*;
 use bevy::{
     asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
     reflect::TypePath,
+    utils::HashSet,
 };
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_asset_loader::<WordListLoader>()
         .init_asset::<WordListSource>();
 }
 
-#[derive(Asset, TypePath, Debug)]
-pub(crate) struct WordListSource(pub Vec<String>);
+#[derive(Asset, TypePath, Debug, Default)]
+pub(crate) struct WordListSource {
+    pub words: Vec<String>,
+    pub metadata: WordListMetadata,
+}
+
+impl WordListSource {
+    /// Words that contain any of the chars that can't be typed as a part of a word.
+    pub fn untypeable_words<'a>(&'a self, blacklist: &'a [char]) -> impl Iterator<Item = &'a str> {
+        self.words
+            .iter()
+            .filter(|w| w.chars().any(|c| blacklist.contains(&c)))
+            .map(String::as_str)
+    }
+}
+
+/// Read from the header lines at the top of the file, e.g. `#! language: en`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct WordListMetadata {
+    pub language: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct WordListLoaderSettings {
+    /// Lines starting with this prefix are skipped.
+    pub comment_prefix: String,
+    /// Lines starting with this prefix at the top of the file are parsed as `key: value` metadata.
+    pub header_prefix: String,
+    /// Words are lowercased by default, phrases keep their case so bosses can ask for capitals.
+    pub lowercase: Option<bool>,
+}
+
+impl Default for WordListLoaderSettings {
+    fn default() -> Self {
+        Self {
+            comment_prefix: "#".to_string(),
+            header_prefix: "#!".to_string(),
+            lowercase: None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum WordListLoaderError {
+    #[error("could not read the word list: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid UTF-8 on lines {lines:?}")]
+    InvalidUtf8 { lines: Vec<usize> },
+    #[error("invalid header on line {line}: expected `key: value`")]
+    InvalidHeader { line: usize },
+}
 
 #[derive(Default)]
 struct WordListLoader;
 
 impl AssetLoader for WordListLoader {
     type Asset = WordListSource;
-    type Settings = ();
-    type Error = std::io::Error;
+    type Settings = WordListLoaderSettings;
+    type Error = WordListLoaderError;
     async fn load<'a>(
         &'a self,
         reader: &'a mut Reader<'_>,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext<'_>,
+        settings: &'a WordListLoaderSettings,
+        load_context: &'a mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut buf = Vec::new();
         reader.read_to_end(&mut buf).await?;
-        // todo: properly handle UTF8 errors
-        let text = String::from_utf8_lossy(&buf);
-        let words = text
-            .lines()
-            .map(|l| l.trim().to_string())
-            .filter(|w| !w.is_empty())
-            .collect();
-        Ok(WordListSource(words))
+        let is_phrase_list = load_context
+            .path()
+            .to_string_lossy()
+            .ends_with(".phrases.txt");
+        let settings = WordListLoaderSettings {
+            lowercase: Some(settings.lowercase.unwrap_or(!is_phrase_list)),
+            ..settings.clone()
+        };
+        parse_word_list(&buf, &settings)
     }
 
+    /// Phrase lists have the same format, with a phrase on each line.
     fn extensions(&self) -> &[&str] {
-        &["words.txt"]
+        &["words.txt", "phrases.txt"]
+    }
+}
+
+/// Word list outside of the assets, e.g. passed as `--wordlist <path>`.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn read_word_list(
+    path: &std::path::Path,
+) -> Result<WordListSource, WordListLoaderError> {
+    parse_word_list(&std::fs::read(path)?, &WordListLoaderSettings::default())
+}
+
+fn parse_word_list(
+    bytes: &[u8],
+    settings: &WordListLoaderSettings,
+) -> Result<WordListSource, WordListLoaderError> {
+    let mut invalid_lines = Vec::new();
+    let lines: Vec<_> = bytes
+        .split(|b| *b == b'\n')
+        .enumerate()
+        .filter_map(|(i, line)| match std::str::from_utf8(line) {
+            Ok(line) => Some((i + 1, line.trim())),
+            Err(_) => {
+                invalid_lines.push(i + 1);
+                None
+            }
+        })
+        .collect();
+    if !invalid_lines.is_empty() {
+        return Err(WordListLoaderError::InvalidUtf8 {
+            lines: invalid_lines,
+        });
+    }
+
+    let mut source = WordListSource::default();
+    let mut seen = HashSet::new();
+    let mut in_header = true;
+    for (line_nr, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let header = line
+            .strip_prefix(&settings.header_prefix)
+            .filter(|_| in_header);
+        if let Some(header) = header {
+            let (key, value) = header
+                .split_once(':')
+                .ok_or(WordListLoaderError::InvalidHeader { line: line_nr })?;
+            let value = value.trim();
+            match key.trim() {
+                "language" => source.metadata.language = Some(value.to_string()),
+                "tags" => source.metadata.tags.extend(
+                    value
+                        .split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty()),
+                ),
+                key => warn!("Unknown word list header key '{key}' on line {line_nr}"),
+            }
+            continue;
+        }
+        if line.starts_with(&settings.comment_prefix) {
+            continue;
+        }
+
+        in_header = false;
+        // phrases are typed with single spaces
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut word: String = line.nfc().collect();
+        if settings.lowercase.unwrap_or(true) {
+            word = word.to_lowercase();
+        }
+        if seen.insert(word.clone()) {
+            source.words.push(word);
+        }
+    }
+
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &[u8]) -> Result<WordListSource, WordListLoaderError> {
+        parse_word_list(text, &WordListLoaderSettings::default())
+    }
+
+    #[test]
+    fn parses_header_and_skips_comments() {
+        let source =
+            parse(b"#! language: cs\n#! tags: common, short\n# comment\nkolo\n\n  pivo \n")
+                .unwrap();
+        assert_eq!(
+            source.metadata,
+            WordListMetadata {
+                language: Some("cs".to_string()),
+                tags: vec!["common".to_string(), "short".to_string()],
+            }
+        );
+        assert_eq!(source.words, vec!["kolo", "pivo"]);
+    }
+
+    #[test]
+    fn normalizes_and_dedupes_words() {
+        // decomposed 'e' + combining acute accent
+        let source = parse("Kolo\nkolo\nkafe\u{301}\nkafé".as_bytes()).unwrap();
+        assert_eq!(source.words, vec!["kolo", "kafé"]);
+    }
+
+    #[test]
+    fn collapses_whitespace_in_phrases() {
+        let source = parse(b"Dig  deeper,\tminer!\n").unwrap();
+        assert_eq!(source.words, vec!["dig deeper, miner!"]);
+    }
+
+    #[test]
+    fn keeps_the_case_when_asked_to() {
+        let settings = WordListLoaderSettings {
+            lowercase: Some(false),
+            ..default()
+        };
+        let source = parse_word_list(b"Dig Deeper\n", &settings).unwrap();
+        assert_eq!(source.words, vec!["Dig Deeper"]);
+    }
+
+    #[test]
+    fn reports_invalid_utf8_lines() {
+        let err = parse(b"kolo\n\xff\xfe\npivo\n\xc3").unwrap_err();
+        assert!(matches!(err, WordListLoaderError::InvalidUtf8 { lines } if lines == vec![2, 4]));
+    }
+
+    #[test]
+    fn reports_invalid_header() {
+        let err = parse(b"#! language\nkolo").unwrap_err();
+        assert!(matches!(
+            err,
+            WordListLoaderError::InvalidHeader { line: 1 }
+        ));
     }
 }